@@ -0,0 +1,105 @@
+use crate::RainmeterContext;
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+use std::sync::Mutex;
+
+/// `measure_id/key` pairs already run by `execute_once`.
+static EXECUTED_ONCE: Lazy<Mutex<HashSet<String>>> = Lazy::new(Default::default);
+
+/// Action options (`OnHigh=[!SetOption ...]`, ...) keyed by option name.
+///
+/// Derefs to the underlying `HashMap`, so lookups work as usual.
+#[derive(Debug, Clone, Default)]
+pub struct ActionMap {
+    actions: HashMap<String, String>,
+}
+
+impl ActionMap {
+    /// Execute the action stored under `name`, returning `false` if there is none.
+    pub fn run(&self, rm: &RainmeterContext, name: &str) -> bool {
+        match self.actions.get(name) {
+            Some(action) => {
+                rm.execute(action);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Deref for ActionMap {
+    type Target = HashMap<String, String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.actions
+    }
+}
+
+impl RainmeterContext {
+    /// Read a set of action options into an [`ActionMap`], skipping the empty
+    /// ones. Section variables are left unexpanded (like Rainmeter does for its
+    /// own action options), so `[Measure]` references inside the actions are
+    /// resolved when the action runs, not when it's read.
+    pub fn read_action_map(&self, keys: &[&str]) -> ActionMap {
+        let actions = keys
+            .iter()
+            .filter_map(|key| {
+                let action = self.read_string_raw(key, "");
+                (!action.trim().is_empty()).then(|| (key.to_string(), action))
+            })
+            .collect();
+        ActionMap { actions }
+    }
+
+    /// Execute `command` the first time this is called with `key` for this
+    /// measure, and do nothing on later calls. Returns whether it ran.
+    ///
+    /// The record lives in the plugin DLL, so it survives skin refreshes and
+    /// `reload`, but is lost when Rainmeter restarts or unloads the plugin
+    /// (which it does once no loaded skin uses it). Keys are scoped per
+    /// measure via [`measure_id`](Self::measure_id).
+    pub fn execute_once(&self, key: &str, command: &str) -> bool {
+        let id = format!("{}/{}", self.measure_id(), key);
+        let first = EXECUTED_ONCE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id);
+        if first {
+            self.execute(command);
+        }
+        first
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::MockContext;
+
+    #[test]
+    fn execute_once_suppresses_repeats() {
+        let rm = MockContext::new();
+        rm.set_measure_name("MeasureSetup");
+        assert!(rm.execute_once("welcome", "[!About]"));
+        assert!(!rm.execute_once("welcome", "[!About]"));
+        assert!(rm.execute_once("tour", "[!Manage]"));
+        assert_eq!(rm.bangs(), ["[!About]", "[!Manage]"]);
+
+        // Another measure has its own record.
+        rm.set_measure_name("MeasureOther");
+        assert!(rm.execute_once("welcome", "[!About]"));
+    }
+
+    #[test]
+    fn reads_non_empty_actions_unexpanded() {
+        let rm = MockContext::new();
+        rm.set("OnHigh", "[!SetOption Meter Text [MeasureCPU]]")
+            .set("OnLow", " ")
+            .set_variable("[MeasureCPU]", "42");
+        let actions = rm.read_action_map(&["OnHigh", "OnLow", "OnMissing"]);
+        assert_eq!(actions.len(), 1);
+        assert!(actions.run(&rm, "OnHigh"));
+        assert!(!actions.run(&rm, "OnLow"));
+        assert_eq!(rm.bangs(), ["[!SetOption Meter Text [MeasureCPU]]"]);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::RainmeterContext;
+use windows::Win32::Foundation::HGLOBAL;
+use windows::Win32::System::DataExchange::{
+    CloseClipboard, GetClipboardData, IsClipboardFormatAvailable, OpenClipboard,
+};
+use windows::Win32::System::Memory::{GlobalLock, GlobalSize, GlobalUnlock};
+
+/// `CF_UNICODETEXT`, defined here to avoid pulling in `Win32_System_Ole` for one constant.
+const CF_UNICODETEXT: u32 = 13;
+
+/// Closes the clipboard when dropped.
+struct OpenedClipboard;
+
+impl OpenedClipboard {
+    fn open() -> Option<Self> {
+        unsafe { OpenClipboard(None) }.ok().map(|_| OpenedClipboard)
+    }
+}
+
+impl Drop for OpenedClipboard {
+    fn drop(&mut self) {
+        let _ = unsafe { CloseClipboard() };
+    }
+}
+
+impl RainmeterContext {
+    /// Current clipboard contents as text, or `None` if the clipboard is
+    /// empty, holds non-text data, or is held open by another application.
+    ///
+    /// The clipboard is opened and closed within this call, on the calling
+    /// thread. Call it from the plugin entry points (Rainmeter's main thread);
+    /// calling it from a worker thread works but can contend with Rainmeter's
+    /// own message loop.
+    pub fn clipboard_text(&self) -> Option<String> {
+        unsafe {
+            IsClipboardFormatAvailable(CF_UNICODETEXT).ok()?;
+            let _clipboard = OpenedClipboard::open()?;
+            let handle = GetClipboardData(CF_UNICODETEXT).ok()?;
+            let global = HGLOBAL(handle.0);
+            let ptr = GlobalLock(global) as *const u16;
+            if ptr.is_null() {
+                return None;
+            }
+            // Don't trust the data to be NUL-terminated within its allocation.
+            let capacity = GlobalSize(global) / 2;
+            let mut len = 0;
+            while len < capacity && *ptr.add(len) != 0 {
+                len += 1;
+            }
+            let text = String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len));
+            let _ = GlobalUnlock(global);
+            Some(text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::DataExchange::{EmptyClipboard, SetClipboardData};
+    use windows::Win32::System::Memory::{GMEM_MOVEABLE, GlobalAlloc};
+
+    /// Put `text` on the clipboard, or return `None` if it can't be opened
+    /// (another application holds it, or there's no desktop session).
+    fn set_clipboard_text(text: &str) -> Option<()> {
+        let wide: Vec<u16> = text.encode_utf16().chain(Some(0)).collect();
+        let _clipboard = OpenedClipboard::open()?;
+        unsafe {
+            EmptyClipboard().ok()?;
+            let global = GlobalAlloc(GMEM_MOVEABLE, wide.len() * 2).ok()?;
+            let ptr = GlobalLock(global) as *mut u16;
+            std::ptr::copy_nonoverlapping(wide.as_ptr(), ptr, wide.len());
+            let _ = GlobalUnlock(global);
+            SetClipboardData(CF_UNICODETEXT, Some(HANDLE(global.0))).ok()?;
+        }
+        Some(())
+    }
+
+    #[test]
+    fn reads_text_set_on_the_clipboard() {
+        let rm = MockContext::new();
+        if set_clipboard_text("rainmeter-rs clipboard test ✓").is_none() {
+            eprintln!("clipboard unavailable, skipping");
+            return;
+        }
+        assert_eq!(
+            rm.clipboard_text().as_deref(),
+            Some("rainmeter-rs clipboard test ✓")
+        );
+    }
+}
@@ -0,0 +1,457 @@
+use crate::registry::read_registry_string;
+use crate::{OptionEnum, RainmeterContext, RmError, RmLogLevel};
+use std::ops::Deref;
+use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+use windows::Win32::System::Registry::HKEY_CURRENT_USER;
+
+/// An `(r, g, b, a)` color, as used throughout Rainmeter.
+pub type Rgba = (u8, u8, u8, u8);
+
+/// Parse a Rainmeter color: `R,G,B[,A]` in decimal or `RRGGBB[AA]` in hex.
+/// A missing alpha means fully opaque.
+pub fn parse_color(value: &str) -> Option<Rgba> {
+    let value = value.trim();
+    if value.contains(',') {
+        let parts: Vec<u8> = value
+            .split(',')
+            .map(|part| part.trim().parse::<u8>().ok())
+            .collect::<Option<_>>()?;
+        return match parts[..] {
+            [r, g, b] => Some((r, g, b, 255)),
+            [r, g, b, a] => Some((r, g, b, a)),
+            _ => None,
+        };
+    }
+
+    if !(value.len() == 6 || value.len() == 8) || !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&value[i..i + 2], 16).ok();
+    let alpha = if value.len() == 8 { channel(6)? } else { 255 };
+    Some((channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+/// A color scale defined by `(value, color)` stops, for heatmaps and gauges.
+///
+/// Derefs to the stops, sorted by value.
+///
+/// ```rust
+/// # use rainmeter::Heatmap;
+/// let heat = Heatmap::new(vec![(0.0, (0, 0, 255, 255)), (100.0, (255, 0, 0, 255))]);
+/// assert_eq!(heat.color_at(50.0), (128, 0, 128, 255));
+/// assert_eq!(heat.color_at(150.0), (255, 0, 0, 255));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Heatmap {
+    stops: Vec<(f64, Rgba)>,
+}
+
+impl Heatmap {
+    /// A scale through `stops`, in any order.
+    pub fn new(mut stops: Vec<(f64, Rgba)>) -> Self {
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { stops }
+    }
+
+    /// The color for `value`, interpolated channel by channel (alpha
+    /// included) between the stops on either side. Values outside the
+    /// stops get the first or last color; an empty scale gives transparent
+    /// black.
+    pub fn color_at(&self, value: f64) -> Rgba {
+        let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+            return (0, 0, 0, 0);
+        };
+        if value.is_nan() || value <= first.0 {
+            return first.1;
+        }
+        if value >= last.0 {
+            return last.1;
+        }
+        let upper = self.stops.iter().position(|stop| stop.0 >= value).unwrap();
+        let ((lo_value, lo), (hi_value, hi)) = (self.stops[upper - 1], self.stops[upper]);
+        let t = (value - lo_value) / (hi_value - lo_value);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        (
+            mix(lo.0, hi.0),
+            mix(lo.1, hi.1),
+            mix(lo.2, hi.2),
+            mix(lo.3, hi.3),
+        )
+    }
+}
+
+impl Deref for Heatmap {
+    type Target = Vec<(f64, Rgba)>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.stops
+    }
+}
+
+/// How a color is composed over another, as in image editors.
+///
+/// Rainmeter meters only draw normally, so a plugin that offers other modes
+/// works them out itself with [`blend`](Self::blend) and hands the result
+/// to a meter (e.g. through `!SetOption ... FontColor`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    /// The top color replaces the base.
+    Normal,
+    /// Channels are added, saturating at 255; brightens.
+    Add,
+    /// Channels are multiplied; darkens.
+    Multiply,
+    /// Inverted channels are multiplied; brightens without clipping as fast
+    /// as `Add`.
+    Screen,
+}
+
+impl OptionEnum for BlendMode {
+    const NAMES: &'static [(&'static str, Self)] = &[
+        ("Normal", BlendMode::Normal),
+        ("Add", BlendMode::Add),
+        ("Multiply", BlendMode::Multiply),
+        ("Screen", BlendMode::Screen),
+    ];
+}
+
+impl BlendMode {
+    /// Compose `top` over `base`. The blended color is mixed with `base` by
+    /// `top`'s alpha, and the result's alpha is that of `top` laid over
+    /// `base`.
+    ///
+    /// ```rust
+    /// # use rainmeter::BlendMode;
+    /// let (base, top) = ((200, 100, 0, 255), (100, 100, 100, 255));
+    /// assert_eq!(BlendMode::Normal.blend(base, top), (100, 100, 100, 255));
+    /// assert_eq!(BlendMode::Add.blend(base, top), (255, 200, 100, 255));
+    /// assert_eq!(BlendMode::Multiply.blend(base, top), (78, 39, 0, 255));
+    /// assert_eq!(BlendMode::Screen.blend(base, top), (222, 161, 100, 255));
+    /// // A half-transparent top color only goes half way.
+    /// assert_eq!(BlendMode::Normal.blend(base, (0, 0, 0, 128)), (100, 50, 0, 255));
+    /// ```
+    pub fn blend(self, base: Rgba, top: Rgba) -> Rgba {
+        let opacity = top.3 as f64 / 255.0;
+        let channel = |b: u8, t: u8| {
+            let (b, t) = (b as f64, t as f64);
+            let blended = match self {
+                BlendMode::Normal => t,
+                BlendMode::Add => (b + t).min(255.0),
+                BlendMode::Multiply => b * t / 255.0,
+                BlendMode::Screen => 255.0 - (255.0 - b) * (255.0 - t) / 255.0,
+            };
+            (b + (blended - b) * opacity).round() as u8
+        };
+        let alpha = top.3 as f64 + base.3 as f64 * (1.0 - opacity);
+        (
+            channel(base.0, top.0),
+            channel(base.1, top.1),
+            channel(base.2, top.2),
+            alpha.round() as u8,
+        )
+    }
+}
+
+/// Whether apps should use the light theme, per Windows' "Choose your default
+/// app mode" setting (`AppsUseLightTheme`). Windows versions without the
+/// setting are treated as light; other registry errors are returned.
+pub fn system_uses_light_theme() -> Result<bool, RmError> {
+    light_theme_from(read_registry_string(
+        HKEY_CURRENT_USER,
+        r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+        "AppsUseLightTheme",
+    ))
+}
+
+/// Interpret the result of reading `AppsUseLightTheme`.
+fn light_theme_from(value: Result<String, RmError>) -> Result<bool, RmError> {
+    match value {
+        Ok(value) => Ok(value.trim() != "0"),
+        Err(RmError::Win32(err)) if err.code() == ERROR_FILE_NOT_FOUND.to_hresult() => Ok(true),
+        Err(err) => Err(err),
+    }
+}
+
+impl RainmeterContext {
+    /// Read a color option (see [`parse_color`]). Invalid values are logged
+    /// and fall back to `default`.
+    pub fn read_color(&self, key: &str, default: Rgba) -> Rgba {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return default;
+        }
+        parse_color(&value).unwrap_or_else(|| {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: invalid color '{}'", key, value),
+            );
+            default
+        })
+    }
+
+    /// Read a color from `color_key` and, if `alpha_key` is set, replace its
+    /// alpha with that value (`0`–`255`, clamped). Handy for skins that split
+    /// `Color=FF8000` and `Alpha=128`.
+    pub fn read_color_with_alpha(&self, color_key: &str, alpha_key: &str, default: Rgba) -> Rgba {
+        let (r, g, b, a) = self.read_color(color_key, default);
+        let alpha = self.read_formula(alpha_key, a as f64);
+        let alpha = if alpha.is_nan() {
+            a
+        } else {
+            alpha.clamp(0.0, 255.0).round() as u8
+        };
+        (r, g, b, alpha)
+    }
+
+    /// Read a color that may be `auto`, which picks `light_default` or
+    /// `dark_default` to match the system app theme (see
+    /// [`system_uses_light_theme`]). Missing or invalid values (the latter
+    /// logged) are treated as `auto`; anything else is parsed as a color.
+    ///
+    /// The theme is looked up on each call, so re-read the option from
+    /// `reload` (or with `DynamicVariables=1`) to follow theme changes. If
+    /// it can't be read, the error is logged and the light default is used.
+    pub fn read_adaptive_color(&self, key: &str, light_default: Rgba, dark_default: Rgba) -> Rgba {
+        self.read_adaptive_color_with(key, light_default, dark_default, system_uses_light_theme)
+    }
+
+    fn read_adaptive_color_with(
+        &self,
+        key: &str,
+        light_default: Rgba,
+        dark_default: Rgba,
+        light_theme: impl FnOnce() -> Result<bool, RmError>,
+    ) -> Rgba {
+        let value = self.read_string(key, "");
+        let value = value.trim();
+        if !value.is_empty() && !value.eq_ignore_ascii_case("auto") {
+            if let Some(color) = parse_color(value) {
+                return color;
+            }
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: invalid color '{}', using auto", key, value),
+            );
+        }
+        let light = light_theme().unwrap_or_else(|err| {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!(
+                    "{}: can't read the system theme ({}), using light",
+                    key, err
+                ),
+            );
+            true
+        });
+        if light { light_default } else { dark_default }
+    }
+
+    /// Read a `|`-separated list of `value:color` stops, such as
+    /// `Heatmap=0:0000FF | 50:00FF00 | 100:FF0000`, into a [`Heatmap`].
+    /// Colors use the [`parse_color`] syntax; malformed stops are logged and
+    /// skipped.
+    pub fn read_heatmap(&self, key: &str) -> Heatmap {
+        Heatmap::new(self.read_list_schema(key, '|', |entry| {
+            let (value, color) = entry.split_once(':')?;
+            let value: f64 = value.trim().parse().ok()?;
+            value.is_finite().then_some((value, parse_color(color)?))
+        }))
+    }
+
+    /// Read a [`BlendMode`] such as `Blend=Multiply` (`Normal`, `Add`,
+    /// `Multiply` or `Screen`). Unknown names are logged and give `default`.
+    pub fn read_blend_mode(&self, key: &str, default: BlendMode) -> BlendMode {
+        self.read_enum(key, default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+    use windows::Win32::Foundation::{ERROR_ACCESS_DENIED, WIN32_ERROR};
+
+    const LIGHT: Rgba = (255, 255, 255, 255);
+    const DARK: Rgba = (0, 0, 0, 255);
+
+    fn win32(code: WIN32_ERROR) -> RmError {
+        RmError::Win32(code.to_hresult().into())
+    }
+
+    #[test]
+    fn reads_theme_setting() {
+        assert!(!light_theme_from(Ok("0".into())).unwrap());
+        assert!(light_theme_from(Ok("1".into())).unwrap());
+        // Windows versions without the setting only have a light theme.
+        assert!(light_theme_from(Err(win32(ERROR_FILE_NOT_FOUND))).unwrap());
+        assert!(light_theme_from(Err(win32(ERROR_ACCESS_DENIED))).is_err());
+    }
+
+    #[test]
+    fn auto_follows_the_theme() {
+        let rm = MockContext::new();
+        rm.set("Color", "auto");
+        assert_eq!(
+            rm.read_adaptive_color_with("Color", LIGHT, DARK, || Ok(true)),
+            LIGHT
+        );
+        assert_eq!(
+            rm.read_adaptive_color_with("Color", LIGHT, DARK, || Ok(false)),
+            DARK
+        );
+        rm.unset("Color");
+        assert_eq!(
+            rm.read_adaptive_color_with("Color", LIGHT, DARK, || Ok(false)),
+            DARK
+        );
+        assert!(rm.warnings().is_empty());
+    }
+
+    #[test]
+    fn theme_errors_are_logged() {
+        let rm = MockContext::new();
+        let color =
+            rm.read_adaptive_color_with("Color", LIGHT, DARK, || Err(win32(ERROR_ACCESS_DENIED)));
+        assert_eq!(color, LIGHT);
+        let warnings = rm.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Color: can't read the system theme"));
+    }
+
+    #[test]
+    fn explicit_colors_ignore_the_theme() {
+        let rm = MockContext::new();
+        rm.set("Color", "FF8000");
+        let theme = || -> Result<bool, RmError> { panic!("theme read for an explicit color") };
+        assert_eq!(
+            rm.read_adaptive_color_with("Color", LIGHT, DARK, theme),
+            (255, 128, 0, 255)
+        );
+
+        rm.set("Color", "orange");
+        assert_eq!(
+            rm.read_adaptive_color_with("Color", LIGHT, DARK, || Ok(false)),
+            DARK
+        );
+        assert_eq!(rm.warnings().len(), 1);
+    }
+
+    #[test]
+    fn parses_decimal_and_hex_colors() {
+        assert_eq!(parse_color("255, 128,0"), Some((255, 128, 0, 255)));
+        assert_eq!(parse_color("1,2,3,4"), Some((1, 2, 3, 4)));
+        assert_eq!(parse_color(" FF8000 "), Some((255, 128, 0, 255)));
+        assert_eq!(parse_color("ff800080"), Some((255, 128, 0, 128)));
+        for invalid in [
+            "",
+            "1,2",
+            "1,2,3,4,5",
+            "256,0,0",
+            "FF80",
+            "GG8000",
+            "#FF8000",
+        ] {
+            assert_eq!(parse_color(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn reads_color_with_separate_alpha() {
+        let rm = MockContext::new();
+        let default = (1, 2, 3, 4);
+        assert_eq!(rm.read_color_with_alpha("Color", "Alpha", default), default);
+
+        rm.set("Color", "FF8000");
+        assert_eq!(
+            rm.read_color_with_alpha("Color", "Alpha", default),
+            (255, 128, 0, 255)
+        );
+        rm.set("Alpha", "128");
+        assert_eq!(
+            rm.read_color_with_alpha("Color", "Alpha", default),
+            (255, 128, 0, 128)
+        );
+        rm.set("Alpha", "300");
+        assert_eq!(
+            rm.read_color_with_alpha("Color", "Alpha", default),
+            (255, 128, 0, 255)
+        );
+        assert!(rm.warnings().is_empty());
+    }
+
+    #[test]
+    fn invalid_colors_fall_back_to_the_default() {
+        let rm = MockContext::new();
+        rm.set("Color", "orange").set("Alpha", "64");
+        assert_eq!(
+            rm.read_color_with_alpha("Color", "Alpha", (1, 2, 3, 4)),
+            (1, 2, 3, 64)
+        );
+        assert_eq!(rm.warnings(), ["Color: invalid color 'orange'"]);
+    }
+
+    #[test]
+    fn heatmap_clamps_to_the_end_stops() {
+        let heat = Heatmap::new(vec![(100.0, (255, 0, 0, 255)), (0.0, (0, 0, 255, 255))]);
+        assert_eq!(heat.color_at(-50.0), (0, 0, 255, 255));
+        assert_eq!(heat.color_at(f64::NAN), (0, 0, 255, 255));
+        assert_eq!(heat.color_at(0.0), (0, 0, 255, 255));
+        assert_eq!(heat.color_at(1e9), (255, 0, 0, 255));
+        assert_eq!(Heatmap::default().color_at(50.0), (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn reads_heatmap_stops() {
+        let rm = MockContext::new();
+        rm.set(
+            "Heatmap",
+            "100:FF0000 | 0:0000FF | 50 : 0,255,0,128 | hot:FF0000 | 75:red",
+        );
+        let heat = rm.read_heatmap("Heatmap");
+        assert_eq!(
+            heat[..],
+            [
+                (0.0, (0, 0, 255, 255)),
+                (50.0, (0, 255, 0, 128)),
+                (100.0, (255, 0, 0, 255)),
+            ]
+        );
+        assert_eq!(heat.color_at(25.0), (0, 128, 128, 192));
+        assert_eq!(
+            rm.warnings(),
+            [
+                "Heatmap: skipping invalid entry 'hot:FF0000'".to_string(),
+                "Heatmap: skipping invalid entry '75:red'".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_blend_modes() {
+        let rm = MockContext::new();
+        assert_eq!(
+            rm.read_blend_mode("Blend", BlendMode::Normal),
+            BlendMode::Normal
+        );
+        for (value, expected) in [
+            ("Normal", BlendMode::Normal),
+            ("add", BlendMode::Add),
+            ("MULTIPLY", BlendMode::Multiply),
+            (" Screen ", BlendMode::Screen),
+        ] {
+            rm.set("Blend", value);
+            assert_eq!(rm.read_blend_mode("Blend", BlendMode::Normal), expected);
+        }
+        assert!(rm.warnings().is_empty());
+
+        rm.set("Blend", "Overlay");
+        assert_eq!(
+            rm.read_blend_mode("Blend", BlendMode::Screen),
+            BlendMode::Screen
+        );
+        assert_eq!(
+            rm.warnings(),
+            ["Blend: unknown value 'Overlay'".to_string()]
+        );
+    }
+}
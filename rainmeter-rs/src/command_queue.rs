@@ -0,0 +1,154 @@
+use crate::{RainmeterContext, RmLogLevel};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A closure queued to run on the Rainmeter thread.
+pub type Command = Box<dyn FnOnce(&RainmeterContext) + Send>;
+
+struct Shared {
+    commands: Mutex<VecDeque<Command>>,
+    capacity: usize,
+    dropped: AtomicUsize,
+}
+
+/// Lets worker threads schedule work that needs a `RainmeterContext` (bangs,
+/// logging, option reads) without holding on to the raw `rm` pointer.
+///
+/// Clone the queue into workers and [`push`](Self::push) closures; call
+/// [`drain`](Self::drain) from `update` to run them on the Rainmeter thread.
+/// The queue is bounded; commands pushed while it is full are dropped and
+/// reported by the next `drain`.
+///
+/// ```rust,ignore
+/// let queue = self.queue.clone();
+/// std::thread::spawn(move || {
+///     let title = fetch_title();
+///     queue.push(move |rm| rm.execute(&format!("[!SetVariable Title \"{}\"]", title)));
+/// });
+///
+/// // in update():
+/// self.queue.drain(&rm);
+/// ```
+#[derive(Clone)]
+pub struct CommandQueue {
+    shared: Arc<Shared>,
+}
+
+impl CommandQueue {
+    /// Create a queue holding at most `capacity` pending commands.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                commands: Mutex::new(VecDeque::new()),
+                capacity,
+                dropped: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Queue `command`. Returns `false` (and drops it) if the queue is full.
+    pub fn push<F>(&self, command: F) -> bool
+    where
+        F: FnOnce(&RainmeterContext) + Send + 'static,
+    {
+        let mut commands = self
+            .shared
+            .commands
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if commands.len() >= self.shared.capacity {
+            self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        commands.push_back(Box::new(command));
+        true
+    }
+
+    /// Number of commands waiting to run.
+    pub fn len(&self) -> usize {
+        self.shared
+            .commands
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+
+    /// Returns `true` if no commands are waiting.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Run all pending commands with `rm`, returning how many ran. Must be
+    /// called from the Rainmeter thread (i.e. inside a plugin entry point).
+    pub fn drain(&self, rm: &RainmeterContext) -> usize {
+        // Take the batch first so commands can push follow-ups without deadlocking.
+        let batch = std::mem::take(
+            &mut *self
+                .shared
+                .commands
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+        );
+        let dropped = self.shared.dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            rm.log(
+                RmLogLevel::LogWarning,
+                &format!("CommandQueue full: dropped {} command(s)", dropped),
+            );
+        }
+        let count = batch.len();
+        for command in batch {
+            command(rm);
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn runs_commands_pushed_from_a_worker() {
+        let rm = MockContext::new();
+        let queue = CommandQueue::new(8);
+
+        let worker = queue.clone();
+        std::thread::spawn(move || {
+            for i in 0..3 {
+                assert!(worker.push(move |rm| rm.execute(&format!("[!SetVariable N {}]", i))));
+            }
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.drain(&rm), 3);
+        assert!(queue.is_empty());
+        assert_eq!(
+            rm.bangs(),
+            [
+                "[!SetVariable N 0]",
+                "[!SetVariable N 1]",
+                "[!SetVariable N 2]"
+            ]
+        );
+    }
+
+    #[test]
+    fn overflow_is_dropped_and_reported() {
+        let rm = MockContext::new();
+        let queue = CommandQueue::new(1);
+
+        assert!(queue.push(|_| {}));
+        assert!(!queue.push(|_| {}));
+        assert_eq!(queue.drain(&rm), 1);
+        assert_eq!(rm.warnings(), ["CommandQueue full: dropped 1 command(s)"]);
+
+        // The count is reset once reported.
+        queue.drain(&rm);
+        assert_eq!(rm.warnings().len(), 1);
+    }
+}
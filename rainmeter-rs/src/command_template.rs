@@ -0,0 +1,162 @@
+use crate::RainmeterContext;
+
+/// A command line with `{name}` placeholders, e.g.
+/// `myapp.exe --file "{path}" --mode {mode}`.
+///
+/// [`render`](Self::render) quotes substituted values following the
+/// `CommandLineToArgvW` rules, so a value always arrives as exactly one
+/// argument, whatever spaces, quotes or backslashes it contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandTemplate {
+    template: String,
+}
+
+impl CommandTemplate {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    /// Substitute `{name}` placeholders with the matching values from `vars`.
+    ///
+    /// A bare placeholder is wrapped in quotes when the value needs them; one
+    /// the template already quotes (`"{path}"`) only has its contents escaped.
+    /// Placeholders without a value in `vars` are left as-is.
+    ///
+    /// ```rust
+    /// # use rainmeter::CommandTemplate;
+    /// let cmd = CommandTemplate::new(r#"app.exe "{file}" {title}"#);
+    /// assert_eq!(
+    ///     cmd.render(&[("file", r"C:\My Files\a.txt"), ("title", "say \"hi\"")]),
+    ///     r#"app.exe "C:\My Files\a.txt" "say \"hi\"""#,
+    /// );
+    /// ```
+    pub fn render(&self, vars: &[(&str, &str)]) -> String {
+        let mut out = String::with_capacity(self.template.len());
+        let mut rest = self.template.as_str();
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start + 1..].find('}') else {
+                break;
+            };
+            let name = &rest[start + 1..start + 1 + len];
+            let after = &rest[start + len + 2..];
+            out.push_str(&rest[..start]);
+            match vars.iter().find(|(n, _)| *n == name) {
+                Some((_, value)) if out.ends_with('"') && after.starts_with('"') => {
+                    push_escaped(&mut out, value)
+                }
+                Some((_, value)) => push_quoted(&mut out, value),
+                None => out.push_str(&rest[start..start + len + 2]),
+            }
+            rest = after;
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Append `value` as a single command-line argument, quoting it if needed.
+fn push_quoted(out: &mut String, value: &str) {
+    if !value.is_empty() && !value.contains([' ', '\t', '\n', '"']) {
+        out.push_str(value);
+        return;
+    }
+    out.push('"');
+    push_escaped(out, value);
+    out.push('"');
+}
+
+/// Append `value` for use between double quotes: quotes are backslash-escaped,
+/// and backslashes are doubled where they precede a quote (including the
+/// closing one).
+fn push_escaped(out: &mut String, value: &str) {
+    let mut backslashes = 0;
+    for c in value.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                out.extend(std::iter::repeat_n('\\', backslashes * 2 + 1));
+                backslashes = 0;
+            }
+            _ => {
+                out.extend(std::iter::repeat_n('\\', backslashes));
+                backslashes = 0;
+            }
+        }
+        if c != '\\' {
+            out.push(c);
+        }
+    }
+    out.extend(std::iter::repeat_n('\\', backslashes * 2));
+}
+
+impl RainmeterContext {
+    /// Read a [`CommandTemplate`] option. Rainmeter variables in it are
+    /// expanded when it's read; `{placeholders}` are left for `render`.
+    pub fn read_command_template(&self, key: &str) -> CommandTemplate {
+        CommandTemplate::new(self.read_string(key, "").trim())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &str, vars: &[(&str, &str)]) -> String {
+        CommandTemplate::new(template).render(vars)
+    }
+
+    #[test]
+    fn doubles_trailing_backslashes_inside_quotes() {
+        assert_eq!(
+            render(r#"app.exe "{path}""#, &[("path", r"C:\My Files\")]),
+            r#"app.exe "C:\My Files\\""#
+        );
+        assert_eq!(
+            render("app.exe {path}", &[("path", r"C:\My Files\")]),
+            r#"app.exe "C:\My Files\\""#
+        );
+        // Without quotes around it, a trailing backslash is harmless.
+        assert_eq!(
+            render("app.exe {path}", &[("path", r"C:\Files\")]),
+            r"app.exe C:\Files\"
+        );
+    }
+
+    #[test]
+    fn empty_values_stay_one_argument() {
+        assert_eq!(
+            render("app.exe {title} -v", &[("title", "")]),
+            r#"app.exe "" -v"#
+        );
+        assert_eq!(
+            render(r#"app.exe "{title}""#, &[("title", "")]),
+            r#"app.exe """#
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_and_unterminated_placeholders() {
+        let vars = [("mode", "fast")];
+        assert_eq!(
+            render("app.exe {other} {mode}", &vars),
+            "app.exe {other} fast"
+        );
+        assert_eq!(render("app.exe {mode} {mode", &vars), "app.exe fast {mode");
+        assert_eq!(render("app.exe {}", &vars), "app.exe {}");
+    }
+
+    #[test]
+    fn renders_adjacent_placeholders() {
+        assert_eq!(
+            render("{dir}{name}", &[("dir", r"C:\Temp\"), ("name", "a.txt")]),
+            r"C:\Temp\a.txt"
+        );
+        assert_eq!(render("{a}{b}", &[("a", "x y"), ("b", "z")]), r#""x y"z"#);
+    }
+}
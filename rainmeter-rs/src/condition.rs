@@ -0,0 +1,132 @@
+use crate::{RainmeterContext, RmLogLevel};
+
+/// Comparison operators accepted in a [`Condition`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Equal,
+    NotEqual,
+}
+
+/// A threshold test such as `> 90` or `<= 10`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Condition {
+    pub op: CompareOp,
+    pub threshold: f64,
+}
+
+impl Condition {
+    /// Parse `<op> <number>`, where `op` is one of `>`, `>=`, `<`, `<=`,
+    /// `==`, `!=`. Whitespace around either part is optional.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        // Two-character operators first, so `>=` isn't read as `>` + `=10`.
+        let (op, rest) = [
+            (">=", CompareOp::GreaterEqual),
+            ("<=", CompareOp::LessEqual),
+            ("==", CompareOp::Equal),
+            ("!=", CompareOp::NotEqual),
+            (">", CompareOp::Greater),
+            ("<", CompareOp::Less),
+        ]
+        .into_iter()
+        .find_map(|(token, op)| input.strip_prefix(token).map(|rest| (op, rest)))?;
+        let threshold = rest.trim().parse::<f64>().ok().filter(|t| !t.is_nan())?;
+        Some(Self { op, threshold })
+    }
+
+    /// Test `value` against the threshold.
+    pub fn eval(&self, value: f64) -> bool {
+        match self.op {
+            CompareOp::Greater => value > self.threshold,
+            CompareOp::GreaterEqual => value >= self.threshold,
+            CompareOp::Less => value < self.threshold,
+            CompareOp::LessEqual => value <= self.threshold,
+            CompareOp::Equal => value == self.threshold,
+            CompareOp::NotEqual => value != self.threshold,
+        }
+    }
+}
+
+impl RainmeterContext {
+    /// Read a condition option like `Condition=>= 90` (see
+    /// [`Condition::parse`]). Returns `None` if the option is missing or
+    /// malformed; malformed values are logged.
+    pub fn read_condition(&self, key: &str) -> Option<Condition> {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return None;
+        }
+        let condition = Condition::parse(&value);
+        if condition.is_none() {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: invalid condition '{}'", key, value),
+            );
+        }
+        condition
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn evaluates_each_operator() {
+        for (input, below, at, above) in [
+            ("< 10", true, false, false),
+            ("<=10", true, true, false),
+            ("> 10", false, false, true),
+            (" >= 10 ", false, true, true),
+            ("== 10", false, true, false),
+            ("!= 10", true, false, true),
+        ] {
+            let condition = Condition::parse(input).unwrap();
+            assert_eq!(condition.threshold, 10.0, "{}", input);
+            assert_eq!(
+                [
+                    condition.eval(9.5),
+                    condition.eval(10.0),
+                    condition.eval(10.5)
+                ],
+                [below, at, above],
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_conditions() {
+        for input in [">", ">>5", "abc", "", "> NaN"] {
+            assert_eq!(Condition::parse(input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn logs_invalid_conditions() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_condition("Condition"), None);
+        rm.set("Condition", ">= 90");
+        assert_eq!(
+            rm.read_condition("Condition"),
+            Some(Condition {
+                op: CompareOp::GreaterEqual,
+                threshold: 90.0
+            })
+        );
+        assert!(rm.warnings().is_empty());
+
+        rm.set("Condition", ">>5");
+        assert_eq!(rm.read_condition("Condition"), None);
+        assert_eq!(
+            rm.warnings(),
+            ["Condition: invalid condition '>>5'".to_string()]
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use crate::RainmeterContext;
+
+/// Response curves for mapping a linear `[0, 1]` level onto a perceptual one.
+///
+/// All curves map `0.0 -> 0.0` and `1.0 -> 1.0`; they differ in how quickly
+/// they rise in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    /// `y = x`
+    Linear,
+    /// `y = log10(1 + 9x)`, rises quickly at the low end.
+    Logarithmic,
+    /// `y = sqrt(x)`, a gentler version of `Logarithmic`.
+    SquareRoot,
+}
+
+impl Curve {
+    /// Apply the curve to `level`, which is clamped to `[0, 1]` first.
+    pub fn apply(self, level: f64) -> f64 {
+        let x = if level.is_nan() {
+            0.0
+        } else {
+            level.clamp(0.0, 1.0)
+        };
+        match self {
+            Curve::Linear => x,
+            Curve::Logarithmic => (1.0 + 9.0 * x).log10(),
+            Curve::SquareRoot => x.sqrt(),
+        }
+    }
+}
+
+impl RainmeterContext {
+    /// Read a `[0, 1]` level (e.g. `Volume=0.5`) and map it through `curve`.
+    /// Missing options read as `0.0`; out-of-range values are clamped.
+    pub fn read_level_curved(&self, key: &str, curve: Curve) -> f64 {
+        curve.apply(self.read_formula(key, 0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn curves_at_zero_half_and_one() {
+        for (curve, half) in [
+            (Curve::Linear, 0.5),
+            (Curve::Logarithmic, 5.5f64.log10()),
+            (Curve::SquareRoot, 0.5f64.sqrt()),
+        ] {
+            assert!(close(curve.apply(0.0), 0.0), "{:?}", curve);
+            assert!(close(curve.apply(0.5), half), "{:?}", curve);
+            assert!(close(curve.apply(1.0), 1.0), "{:?}", curve);
+        }
+    }
+
+    #[test]
+    fn levels_are_clamped() {
+        for curve in [Curve::Linear, Curve::Logarithmic, Curve::SquareRoot] {
+            assert_eq!(curve.apply(-1.0), 0.0);
+            assert_eq!(curve.apply(f64::NAN), 0.0);
+            assert!(close(curve.apply(2.0), 1.0));
+        }
+    }
+
+    #[test]
+    fn reads_curved_levels() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_level_curved("Volume", Curve::SquareRoot), 0.0);
+        rm.set("Volume", "0.25");
+        assert_eq!(rm.read_level_curved("Volume", Curve::SquareRoot), 0.5);
+        rm.set("Volume", "4");
+        assert!(close(
+            rm.read_level_curved("Volume", Curve::Logarithmic),
+            1.0
+        ));
+    }
+}
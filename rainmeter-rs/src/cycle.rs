@@ -0,0 +1,141 @@
+use crate::RainmeterContext;
+
+/// Order in which a [`Cycler`] steps through its items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CycleOrder {
+    /// First to last, then wrap around.
+    Sequential,
+    /// A random item each step (never the same one twice in a row), from a
+    /// generator seeded with `seed`. The same seed gives the same sequence.
+    Random { seed: u64 },
+}
+
+/// Rotates through a list of values, e.g. quotes or wallpapers shown one per
+/// update.
+///
+/// ```rust
+/// # use rainmeter::{CycleOrder, Cycler};
+/// let mut quotes = Cycler::new(vec!["a", "b", "c"], CycleOrder::Sequential);
+/// assert_eq!(quotes.current(), Some(&"a"));
+/// assert_eq!(quotes.advance(), Some(&"b"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cycler<T> {
+    items: Vec<T>,
+    index: usize,
+    order: CycleOrder,
+    rng: u64,
+}
+
+impl<T> Cycler<T> {
+    /// Create a cycler over `items`. Sequential cyclers start at the first
+    /// item, random ones at a random item.
+    pub fn new(items: Vec<T>, order: CycleOrder) -> Self {
+        let rng = match order {
+            // xorshift gets stuck at zero, so swap in an arbitrary odd constant.
+            CycleOrder::Random { seed: 0 } => 0x9E37_79B9_7F4A_7C15,
+            CycleOrder::Random { seed } => seed,
+            CycleOrder::Sequential => 0,
+        };
+        let mut cycler = Self {
+            items,
+            index: 0,
+            order,
+            rng,
+        };
+        if matches!(order, CycleOrder::Random { .. }) && !cycler.items.is_empty() {
+            cycler.index = cycler.next_random() as usize % cycler.items.len();
+        }
+        cycler
+    }
+
+    /// The current item, or `None` if there are no items.
+    pub fn current(&self) -> Option<&T> {
+        self.items.get(self.index)
+    }
+
+    /// Step to the next item and return it.
+    pub fn advance(&mut self) -> Option<&T> {
+        let len = self.items.len();
+        if len > 1 {
+            self.index = match self.order {
+                CycleOrder::Sequential => (self.index + 1) % len,
+                CycleOrder::Random { .. } => {
+                    // Pick among the other items, so the value always changes.
+                    let step = 1 + self.next_random() as usize % (len - 1);
+                    (self.index + step) % len
+                }
+            };
+        }
+        self.current()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// xorshift64*: plenty for picking display items, and needs no dependency.
+    fn next_random(&mut self) -> u64 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+impl RainmeterContext {
+    /// Read a `sep`-separated list (e.g. `Items=a|b|c`, see
+    /// [`read_string_list`](Self::read_string_list)) into a [`Cycler`].
+    pub fn read_cycler(&self, key: &str, sep: char, order: CycleOrder) -> Cycler<String> {
+        Cycler::new(self.read_string_list(key, sep), order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    fn take<T: Clone>(cycler: &mut Cycler<T>, n: usize) -> Vec<T> {
+        let mut items = vec![cycler.current().unwrap().clone()];
+        for _ in 1..n {
+            items.push(cycler.advance().unwrap().clone());
+        }
+        items
+    }
+
+    #[test]
+    fn sequential_wraps_around() {
+        let rm = MockContext::new();
+        rm.set("Items", "a | b | c");
+        let mut cycler = rm.read_cycler("Items", '|', CycleOrder::Sequential);
+        assert_eq!(take(&mut cycler, 7), ["a", "b", "c", "a", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn random_order_is_seeded() {
+        let items = vec![1, 2, 3, 4, 5];
+        let mut a = Cycler::new(items.clone(), CycleOrder::Random { seed: 42 });
+        let mut b = Cycler::new(items.clone(), CycleOrder::Random { seed: 42 });
+        let sequence = take(&mut a, 50);
+        assert_eq!(sequence, take(&mut b, 50));
+        assert!(sequence.windows(2).all(|pair| pair[0] != pair[1]));
+
+        let mut zero = Cycler::new(items, CycleOrder::Random { seed: 0 });
+        let zeros = take(&mut zero, 50);
+        assert!(zeros.windows(2).all(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn empty_and_single_item_cyclers() {
+        let mut empty = Cycler::<u8>::new(Vec::new(), CycleOrder::Random { seed: 1 });
+        assert_eq!(empty.current(), None);
+        assert_eq!(empty.advance(), None);
+        let mut single = Cycler::new(vec!["only"], CycleOrder::Random { seed: 1 });
+        assert_eq!(single.advance(), Some(&"only"));
+    }
+}
@@ -0,0 +1,243 @@
+//! Tabular data files (CSV, TSV, JSON) for data-display measures.
+
+use crate::options::lookup_mapped;
+use crate::{RainmeterContext, RmError, RmLogLevel};
+
+/// Format of a tabular data file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    /// Comma-separated values with RFC 4180 quoting (`"a, b"`, `""` for a
+    /// quote, line breaks inside quotes).
+    Csv,
+    /// Tab-separated values, one row per line, without quoting.
+    Tsv,
+    /// A JSON array of rows, where each row is an array of cells or an
+    /// object. For objects, the first row of the result holds the first
+    /// object's keys (in alphabetical order) and each row lists the values
+    /// for those keys. Needs the `json` feature.
+    Json,
+}
+
+const FORMAT_NAMES: &[(&str, DataFormat)] = &[
+    ("csv", DataFormat::Csv),
+    ("tsv", DataFormat::Tsv),
+    ("json", DataFormat::Json),
+];
+
+/// Parse CSV `text` into rows of cells.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    cell.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if cell.is_empty() => in_quotes = true,
+            ',' if !in_quotes => row.push(std::mem::take(&mut cell)),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+    rows
+}
+
+/// Parse TSV `text` into rows of cells.
+fn parse_tsv(text: &str) -> Vec<Vec<String>> {
+    text.lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.split('\t').map(String::from).collect())
+        .collect()
+}
+
+#[cfg(feature = "json")]
+fn parse_json(text: &str, path: &str) -> Result<Vec<Vec<String>>, RmError> {
+    use crate::JsonExtract;
+    use serde_json::Value;
+
+    let invalid = |message: String| RmError::ParseFailed {
+        key: path.to_string(),
+        message,
+    };
+    let doc: Value = serde_json::from_str(text).map_err(|err| invalid(err.to_string()))?;
+    let Value::Array(items) = doc else {
+        return Err(invalid("expected an array of rows".to_string()));
+    };
+    let cell = |value: &Value| JsonExtract::get(value, "").unwrap_or_default();
+
+    let mut rows = Vec::new();
+    let mut header: Option<Vec<String>> = None;
+    for item in &items {
+        match item {
+            Value::Array(cells) => rows.push(cells.iter().map(cell).collect()),
+            Value::Object(fields) => {
+                let keys = header.get_or_insert_with(|| {
+                    let keys: Vec<String> = fields.keys().cloned().collect();
+                    rows.push(keys.clone());
+                    keys
+                });
+                rows.push(
+                    keys.iter()
+                        .map(|key| fields.get(key).map(cell).unwrap_or_default())
+                        .collect(),
+                );
+            }
+            other => rows.push(vec![cell(other)]),
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(not(feature = "json"))]
+fn parse_json(_text: &str, path: &str) -> Result<Vec<Vec<String>>, RmError> {
+    Err(RmError::ParseFailed {
+        key: path.to_string(),
+        message: "JSON files need the `json` feature".to_string(),
+    })
+}
+
+impl DataFormat {
+    /// Parse `csv`, `tsv` or `json`, ignoring case.
+    pub fn parse(name: &str) -> Option<Self> {
+        lookup_mapped(name, FORMAT_NAMES)
+    }
+
+    /// Guess the format from a file extension (`.csv`, `.tsv`/`.tab`,
+    /// `.json`).
+    pub fn from_extension(path: &str) -> Option<Self> {
+        let extension = std::path::Path::new(path).extension()?.to_str()?;
+        match extension.to_ascii_lowercase().as_str() {
+            "tab" => Some(DataFormat::Tsv),
+            other => Self::parse(other),
+        }
+    }
+
+    /// Parse `text` in this format into rows of cells. `source` names the
+    /// data in errors.
+    pub fn parse_text(self, text: &str, source: &str) -> Result<Vec<Vec<String>>, RmError> {
+        match self {
+            DataFormat::Csv => Ok(parse_csv(text)),
+            DataFormat::Tsv => Ok(parse_tsv(text)),
+            DataFormat::Json => parse_json(text, source),
+        }
+    }
+}
+
+impl RainmeterContext {
+    /// Read a data format option such as `Format=CSV`. Unknown values are
+    /// logged and give `default`.
+    pub fn read_format(&self, key: &str, default: DataFormat) -> DataFormat {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return default;
+        }
+        DataFormat::parse(&value).unwrap_or_else(|| {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: unknown format '{}'", key, value),
+            );
+            default
+        })
+    }
+
+    /// Read the file at `path` (resolved relative to the skin) as UTF-8 and
+    /// parse it as `format` into rows of cells. A UTF-8 byte order mark is
+    /// ignored.
+    ///
+    /// ```rust,ignore
+    /// let format = rm.read_format("Format", DataFormat::Csv);
+    /// let rows = rm.parse_file(&rm.read_string("File", ""), format)?;
+    /// ```
+    pub fn parse_file(&self, path: &str, format: DataFormat) -> Result<Vec<Vec<String>>, RmError> {
+        let path = self.path_to_absolute(path);
+        let text = std::fs::read_to_string(&path)?;
+        format.parse_text(text.strip_prefix('\u{feff}').unwrap_or(&text), &path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    fn rows(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect()
+    }
+
+    /// A mock whose skin folder is a fresh temp directory containing `files`.
+    fn skin_with(name: &str, files: &[(&str, &str)]) -> MockContext {
+        let dir =
+            std::env::temp_dir().join(format!("rainmeter-rs-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (file, contents) in files {
+            std::fs::write(dir.join(file), contents).unwrap();
+        }
+        let rm = MockContext::new();
+        rm.set_skin_path(&dir);
+        rm
+    }
+
+    #[test]
+    fn parses_quoted_csv_files() {
+        let rm = skin_with(
+            "csv",
+            &[(
+                "data.csv",
+                "\u{feff}name,note\r\n\"Doe, John\",\"say \"\"hi\"\"\"\r\n\"two\r\nlines\",\r\nplain,x\"y",
+            )],
+        );
+        assert_eq!(
+            rm.parse_file("data.csv", DataFormat::Csv).unwrap(),
+            rows(&[
+                &["name", "note"],
+                &["Doe, John", "say \"hi\""],
+                &["two\r\nlines", ""],
+                &["plain", "x\"y"],
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_tsv_files() {
+        let rm = skin_with("tsv", &[("data.tsv", "a\tb, c\t\"d\"\r\n\r\n1\t\t3\n")]);
+        assert_eq!(
+            rm.parse_file("data.tsv", DataFormat::Tsv).unwrap(),
+            rows(&[&["a", "b, c", "\"d\""], &["1", "", "3"]])
+        );
+        assert!(rm.parse_file("missing.tsv", DataFormat::Tsv).is_err());
+    }
+
+    #[test]
+    fn reads_formats() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_format("Format", DataFormat::Csv), DataFormat::Csv);
+        rm.set("Format", " TSV ");
+        assert_eq!(rm.read_format("Format", DataFormat::Csv), DataFormat::Tsv);
+        rm.set("Format", "xml");
+        assert_eq!(rm.read_format("Format", DataFormat::Csv), DataFormat::Csv);
+        assert_eq!(rm.warnings(), ["Format: unknown format 'xml'".to_string()]);
+
+        assert_eq!(DataFormat::from_extension("a.TAB"), Some(DataFormat::Tsv));
+        assert_eq!(DataFormat::from_extension("a.json"), Some(DataFormat::Json));
+        assert_eq!(DataFormat::from_extension("a.txt"), None);
+    }
+}
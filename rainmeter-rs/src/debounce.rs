@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+/// Coalesces rapid triggers (e.g. a bang fired on every mouse move) so the
+/// expensive work behind them runs at most once per interval.
+///
+/// ```rust
+/// # use rainmeter::Debouncer;
+/// # use std::time::{Duration, Instant};
+/// let mut debouncer = Debouncer::new();
+/// if debouncer.should_fire(Instant::now(), Duration::from_millis(250)) {
+///     // do the expensive work
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Debouncer {
+    last_fired: Option<Instant>,
+}
+
+impl Debouncer {
+    /// Create a debouncer that will fire on its first trigger.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` (and records `now`) if at least `min_interval` has
+    /// passed since the last time this returned `true`.
+    pub fn should_fire(&mut self, now: Instant, min_interval: Duration) -> bool {
+        match self.last_fired {
+            Some(last) if now.saturating_duration_since(last) < min_interval => false,
+            _ => {
+                self.last_fired = Some(now);
+                true
+            }
+        }
+    }
+
+    /// Forget the last trigger, so the next call to `should_fire` fires.
+    pub fn reset(&mut self) {
+        self.last_fired = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_millis(250);
+
+    #[test]
+    fn fires_once_per_interval() {
+        let base = Instant::now();
+        let mut debouncer = Debouncer::new();
+        assert!(debouncer.should_fire(base, INTERVAL));
+        assert!(!debouncer.should_fire(base, INTERVAL));
+        assert!(!debouncer.should_fire(base + Duration::from_millis(249), INTERVAL));
+        assert!(debouncer.should_fire(base + INTERVAL, INTERVAL));
+        // The interval restarts from the last firing, not the first trigger.
+        assert!(!debouncer.should_fire(base + Duration::from_millis(400), INTERVAL));
+        assert!(debouncer.should_fire(base + Duration::from_millis(500), INTERVAL));
+    }
+
+    #[test]
+    fn reset_and_earlier_instants() {
+        let base = Instant::now();
+        let mut debouncer = Debouncer::new();
+        assert!(debouncer.should_fire(base + INTERVAL, INTERVAL));
+        // An instant before the last firing counts as no time passed.
+        assert!(!debouncer.should_fire(base, INTERVAL));
+        debouncer.reset();
+        assert!(debouncer.should_fire(base, INTERVAL));
+    }
+}
@@ -0,0 +1,196 @@
+//! The `DebugOverlay=1` measure option, for plugin development. While it's
+//! set, `declare_plugin!` replaces the measure's string value with a
+//! multi-line dump of the last `update` value, the options the plugin read,
+//! the last warning or error it logged and the plugin's own
+//! [`debug_overlay_string`](crate::RainmeterPlugin::debug_overlay_string),
+//! so the internals show up on any String meter bound to the measure.
+//!
+//! As with tracing, the cost for measures without the option is a single
+//! relaxed atomic load per option read or log call.
+
+use std::ffi::c_void;
+use std::fmt::{Display, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The option that enables the overlay on a measure.
+pub(crate) const OVERLAY_OPTION: &str = "DebugOverlay";
+
+/// Fast path: `false` whenever `OVERLAYS` is empty.
+static ANY_ENABLED: AtomicBool = AtomicBool::new(false);
+/// State collected for each measure (by `rm` pointer) with the overlay on.
+static OVERLAYS: Mutex<Vec<(usize, Overlay)>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Default)]
+struct Overlay {
+    value: Option<f64>,
+    options: Vec<(String, String)>,
+    last_error: Option<String>,
+}
+
+/// Run `f` on the measure's overlay state, if it has the overlay enabled.
+fn with_overlay(raw: *mut c_void, f: impl FnOnce(&mut Overlay)) {
+    if !ANY_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let mut overlays = OVERLAYS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((_, overlay)) = overlays.iter_mut().find(|(r, _)| *r == raw as usize) {
+        f(overlay);
+    }
+}
+
+/// Enable or disable the overlay. Re-enabling (on reload) forgets the
+/// options read so far, since the plugin is about to read them again.
+pub(crate) fn set_enabled(raw: *mut c_void, enabled: bool) {
+    let mut overlays = OVERLAYS.lock().unwrap_or_else(|e| e.into_inner());
+    match overlays.iter().position(|(r, _)| *r == raw as usize) {
+        Some(index) if enabled => overlays[index].1.options.clear(),
+        Some(index) => {
+            overlays.swap_remove(index);
+        }
+        None if enabled => overlays.push((raw as usize, Overlay::default())),
+        None => {}
+    }
+    ANY_ENABLED.store(!overlays.is_empty(), Ordering::Relaxed);
+}
+
+pub(crate) fn is_enabled(raw: *mut c_void) -> bool {
+    let mut enabled = false;
+    with_overlay(raw, |_| enabled = true);
+    enabled
+}
+
+/// Record an option read; later reads of the same key replace earlier ones.
+pub(crate) fn note_option(raw: *mut c_void, key: &str, value: impl Display) {
+    with_overlay(raw, |overlay| {
+        let value = value.to_string();
+        match overlay
+            .options
+            .iter_mut()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        {
+            Some((_, existing)) => *existing = value,
+            None => overlay.options.push((key.to_string(), value)),
+        }
+    });
+}
+
+/// Record an option read from another section, listed as `[section]key`.
+pub(crate) fn note_section_option(raw: *mut c_void, section: &str, key: &str, value: impl Display) {
+    if ANY_ENABLED.load(Ordering::Relaxed) {
+        note_option(raw, &format!("[{}]{}", section, key), value);
+    }
+}
+
+pub(crate) fn note_value(raw: *mut c_void, value: f64) {
+    with_overlay(raw, |overlay| overlay.value = Some(value));
+}
+
+pub(crate) fn note_error(raw: *mut c_void, message: &str) {
+    with_overlay(raw, |overlay| {
+        overlay.last_error = Some(message.to_string())
+    });
+}
+
+/// The overlay text for the measure, or `None` if it isn't enabled.
+pub(crate) fn render(raw: *mut c_void, measure: &str, plugin: &str) -> Option<String> {
+    let mut text = None;
+    with_overlay(raw, |overlay| {
+        let mut out = format!("{} (DebugOverlay)\n", measure);
+        match overlay.value {
+            Some(value) => writeln!(out, "Value: {}", value),
+            None => writeln!(out, "Value: (not updated yet)"),
+        }
+        .unwrap();
+        writeln!(
+            out,
+            "Last error: {}",
+            overlay.last_error.as_deref().unwrap_or("(none)")
+        )
+        .unwrap();
+        out.push_str("Options:\n");
+        for (key, value) in &overlay.options {
+            writeln!(out, "  {}={}", key, value).unwrap();
+        }
+        out.push_str(plugin);
+        text = Some(out.trim_end().to_string());
+    });
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RmLogLevel;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn report_lists_value_options_and_last_error() {
+        let rm = MockContext::new();
+        rm.set("DebugOverlay", "1").set("Url", "").set("Rate", "2");
+        rm.refresh_debug_overlay();
+        assert!(rm.debug_overlay_enabled());
+
+        rm.read_string("Url", "");
+        rm.read_formula("Rate", 1.0);
+        rm.read_formula("rate", 1.0);
+        assert_eq!(
+            rm.debug_overlay_report("Plugin: Test").unwrap(),
+            "MeasureTest (DebugOverlay)\nValue: (not updated yet)\nLast error: (none)\n\
+             Options:\n  Url=\n  Rate=2\nPlugin: Test"
+        );
+
+        rm.note_update_value(21.5);
+        rm.log(RmLogLevel::LogWarning, "Url: invalid URL ''");
+        rm.log(RmLogLevel::LogNotice, "fetched");
+        let report = rm.debug_overlay_report("Plugin: Test").unwrap();
+        assert!(report.contains("\nValue: 21.5\n"), "{}", report);
+        assert!(
+            report.contains("\nLast error: Url: invalid URL ''\n"),
+            "{}",
+            report
+        );
+    }
+
+    #[test]
+    fn reload_forgets_options_and_finalize_disables() {
+        let rm = MockContext::new();
+        rm.set("DebugOverlay", "1").set("Rate", "2");
+        rm.refresh_debug_overlay();
+        rm.read_formula("Rate", 1.0);
+        rm.refresh_debug_overlay();
+        let report = rm.debug_overlay_report("").unwrap();
+        assert!(!report.contains("Rate"), "{}", report);
+
+        rm.clear_debug_overlay();
+        assert!(!rm.debug_overlay_enabled());
+        assert_eq!(rm.debug_overlay_report(""), None);
+    }
+
+    #[test]
+    fn measures_without_the_option_get_no_report() {
+        let rm = MockContext::new();
+        rm.refresh_debug_overlay();
+        rm.note_update_value(1.0);
+        assert_eq!(rm.debug_overlay_report("Plugin: Test"), None);
+    }
+
+    #[test]
+    fn section_reads_are_listed_with_their_section() {
+        let rm = MockContext::new();
+        rm.set("DebugOverlay", "1")
+            .set_in("Work", "Url", "https://example.com")
+            .set_in("Work", "Rate", "5");
+        rm.refresh_debug_overlay();
+        rm.read_string_section("Work", "Url", "");
+        rm.read_formula_section("Work", "Rate", 1.0);
+        rm.read_formula_section("Home", "Rate", 1.0);
+        let report = rm.debug_overlay_report("").unwrap();
+        assert!(
+            report.ends_with(
+                "Options:\n  [Work]Url=https://example.com\n  [Work]Rate=5\n  [Home]Rate=1"
+            ),
+            "{}",
+            report
+        );
+    }
+}
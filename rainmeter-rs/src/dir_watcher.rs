@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// File count and newest modification time, or `None` if the directory
+/// can't be listed.
+type Signature = Option<(usize, Option<SystemTime>)>;
+
+/// Result of a [`DirWatcher::check`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirStatus {
+    /// Number of files directly in the directory (subdirectories aren't
+    /// counted or descended into). `0` if the directory doesn't exist.
+    pub file_count: usize,
+    /// Whether anything changed since the previous check. Always `true` on
+    /// the first check.
+    pub changed: bool,
+}
+
+/// Polls a directory for changes, e.g. for an "N files in Downloads" skin.
+///
+/// Changes are detected with a cheap signature: the file count plus the
+/// newest modification time. That catches files being added, removed or
+/// rewritten, but not a rename that keeps both the same. Tick it from
+/// `update`; each check lists the directory once.
+#[derive(Debug, Clone)]
+pub struct DirWatcher {
+    path: PathBuf,
+    last: Option<Signature>,
+}
+
+impl DirWatcher {
+    /// Watch `path`, which should already be absolute (see
+    /// [`read_path`](crate::RainmeterContext::read_path)).
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            last: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// List the directory and compare it against the previous check. A
+    /// missing or unreadable directory has no files, but is told apart from
+    /// an empty one, so it reports a change when it disappears and again
+    /// when it comes back.
+    pub fn check(&mut self) -> DirStatus {
+        let signature = Self::signature(&self.path);
+        let changed = self.last != Some(signature);
+        self.last = Some(signature);
+        DirStatus {
+            file_count: signature.map_or(0, |(count, _)| count),
+            changed,
+        }
+    }
+
+    /// Forget the previous check, so the next one reports a change.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+
+    fn signature(path: &Path) -> Signature {
+        let entries = fs::read_dir(path).ok()?;
+        let mut count = 0;
+        let mut newest = None;
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            count += 1;
+            if let Ok(modified) = metadata.modified() {
+                newest = newest.max(Some(modified));
+            }
+        }
+        Some((count, newest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notices_files_being_added() {
+        let dir =
+            std::env::temp_dir().join(format!("rainmeter-rs-dir-watcher-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut watcher = DirWatcher::new(&dir);
+        let status = |file_count, changed| DirStatus {
+            file_count,
+            changed,
+        };
+
+        assert_eq!(watcher.check(), status(0, true));
+        assert_eq!(watcher.check(), status(0, false));
+        // Appearing empty is still a change.
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        assert_eq!(watcher.check(), status(0, true));
+
+        fs::write(dir.join("a.txt"), "a").unwrap();
+        assert_eq!(watcher.check(), status(1, true));
+        assert_eq!(watcher.check(), status(1, false));
+
+        watcher.reset();
+        assert_eq!(watcher.check(), status(1, true));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(watcher.check(), status(0, true));
+    }
+}
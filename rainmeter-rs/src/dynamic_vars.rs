@@ -0,0 +1,103 @@
+use crate::{RainmeterContext, RmLogLevel};
+use std::cell::Cell;
+
+thread_local! {
+    /// Set by every option reader; Rainmeter calls all plugin entry points on
+    /// its main thread, so a thread-local is enough to track "reads since X".
+    static OPTION_READ: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Called by the `read_*` primitives on `RainmeterContext`.
+pub(crate) fn note_option_read() {
+    OPTION_READ.with(|flag| flag.set(true));
+}
+
+/// Warns (once) when a plugin reads options during `update` on a measure
+/// without `DynamicVariables=1`, where those reads only ever see the values
+/// from the last reload.
+///
+/// ```rust,ignore
+/// fn reload(&mut self, rm: RainmeterContext, _max: &mut f64) {
+///     self.guard = DynamicVarsGuard::install(&rm);
+/// }
+///
+/// fn update(&mut self, rm: RainmeterContext) -> f64 {
+///     self.guard.begin_update();
+///     let value = rm.read_double("Value", 0.0);
+///     self.guard.end_update(&rm);
+///     value
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct DynamicVarsGuard {
+    dynamic: bool,
+    warned: bool,
+}
+
+impl DynamicVarsGuard {
+    /// Create a guard for the measure, reading its `DynamicVariables` option.
+    pub fn install(rm: &RainmeterContext) -> Self {
+        Self {
+            dynamic: rm.read_int("DynamicVariables", 0) != 0,
+            warned: false,
+        }
+    }
+
+    /// Mark the start of an update; reads from here on are tracked.
+    pub fn begin_update(&mut self) {
+        OPTION_READ.with(|flag| flag.set(false));
+    }
+
+    /// Mark the end of an update, warning if options were read in between
+    /// while `DynamicVariables` is off. Warns at most once per guard.
+    pub fn end_update(&mut self, rm: &RainmeterContext) {
+        let read = OPTION_READ.with(|flag| flag.replace(false));
+        if read && !self.dynamic && !self.warned {
+            self.warned = true;
+            rm.log(
+                RmLogLevel::LogWarning,
+                "Options are read during Update, but DynamicVariables is off, so they \
+                 won't change. Add DynamicVariables=1 to re-read options each update.",
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    fn update(guard: &mut DynamicVarsGuard, rm: &RainmeterContext, read: bool) {
+        guard.begin_update();
+        if read {
+            rm.read_double("Value", 0.0);
+        }
+        guard.end_update(rm);
+    }
+
+    #[test]
+    fn warns_once_when_reading_without_dynamic_variables() {
+        let rm = MockContext::new();
+        let mut guard = DynamicVarsGuard::install(&rm);
+
+        update(&mut guard, &rm, false);
+        assert!(rm.warnings().is_empty());
+
+        update(&mut guard, &rm, true);
+        update(&mut guard, &rm, true);
+        let warnings = rm.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("DynamicVariables=1"));
+    }
+
+    #[test]
+    fn stays_quiet_with_dynamic_variables() {
+        let rm = MockContext::new();
+        rm.set("DynamicVariables", "1");
+        let mut guard = DynamicVarsGuard::install(&rm);
+
+        update(&mut guard, &rm, true);
+        assert!(rm.warnings().is_empty());
+    }
+}
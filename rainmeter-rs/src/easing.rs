@@ -0,0 +1,123 @@
+use crate::options::lookup_mapped;
+use crate::{RainmeterContext, RmLogLevel};
+
+/// Easing functions for animating between two values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    /// Quadratic ease-in (starts slow).
+    EaseIn,
+    /// Quadratic ease-out (ends slow).
+    EaseOut,
+    /// Quadratic ease-in for the first half, ease-out for the second.
+    EaseInOut,
+    /// Ease-out that bounces against the end value.
+    Bounce,
+}
+
+const EASING_NAMES: &[(&str, Easing)] = &[
+    ("Linear", Easing::Linear),
+    ("EaseIn", Easing::EaseIn),
+    ("EaseOut", Easing::EaseOut),
+    ("EaseInOut", Easing::EaseInOut),
+    ("Bounce", Easing::Bounce),
+];
+
+impl Easing {
+    /// Map animation progress `t` (clamped to `[0, 1]`) to eased progress.
+    /// Every easing maps `0 -> 0` and `1 -> 1`.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Easing::Bounce => bounce_out(t),
+        }
+    }
+}
+
+/// The classic "easeOutBounce" curve (four decaying parabolic bounces).
+fn bounce_out(t: f64) -> f64 {
+    const N: f64 = 7.5625;
+    const D: f64 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+impl RainmeterContext {
+    /// Read an easing by name (`Linear`, `EaseIn`, `EaseOut`, `EaseInOut`,
+    /// `Bounce`; case-insensitive). Unknown names are logged and fall back to
+    /// `default`.
+    pub fn read_easing(&self, key: &str, default: Easing) -> Easing {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return default;
+        }
+        lookup_mapped(&value, EASING_NAMES).unwrap_or_else(|| {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: unknown easing '{}', using {:?}", key, value, default),
+            );
+            default
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn easings_at_zero_half_and_one() {
+        for (easing, half) in [
+            (Easing::Linear, 0.5),
+            (Easing::EaseIn, 0.25),
+            (Easing::EaseOut, 0.75),
+            (Easing::EaseInOut, 0.5),
+            (Easing::Bounce, 0.765625),
+        ] {
+            assert!(easing.apply(0.0).abs() < 1e-12, "{:?}", easing);
+            assert!((easing.apply(0.5) - half).abs() < 1e-12, "{:?}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-12, "{:?}", easing);
+            assert_eq!(easing.apply(-1.0), easing.apply(0.0));
+            assert_eq!(easing.apply(2.0), easing.apply(1.0));
+        }
+    }
+
+    #[test]
+    fn reads_easing_names() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_easing("Easing", Easing::Linear), Easing::Linear);
+        for (name, easing) in EASING_NAMES {
+            rm.set("Easing", &name.to_lowercase());
+            assert_eq!(rm.read_easing("Easing", Easing::Linear), *easing);
+        }
+        assert!(rm.warnings().is_empty());
+
+        rm.set("Easing", "Elastic");
+        assert_eq!(rm.read_easing("Easing", Easing::EaseOut), Easing::EaseOut);
+        assert_eq!(
+            rm.warnings(),
+            ["Easing: unknown easing 'Elastic', using EaseOut"]
+        );
+    }
+}
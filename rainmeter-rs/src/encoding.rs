@@ -0,0 +1,159 @@
+//! Text encodings for files a plugin writes, e.g. exports read by other
+//! tools.
+
+use crate::{OptionEnum, RainmeterContext, RmError};
+use windows::Win32::Globalization::{CP_ACP, WideCharToMultiByte};
+use windows::core::PCSTR;
+
+/// How [`write_text`](RainmeterContext::write_text) encodes a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8 without a byte order mark.
+    Utf8,
+    /// UTF-8 with a byte order mark (`EF BB BF`), which some Windows tools
+    /// need to tell it from ANSI.
+    Utf8Bom,
+    /// UTF-16 little endian with a byte order mark (`FF FE`), the format
+    /// Rainmeter itself uses for `Rainmeter.ini`.
+    Utf16,
+    /// The system's ANSI code page. Characters it can't represent become
+    /// `?`.
+    Ansi,
+}
+
+impl OptionEnum for Encoding {
+    const NAMES: &'static [(&'static str, Self)] = &[
+        ("UTF-8", Encoding::Utf8),
+        ("UTF8", Encoding::Utf8),
+        ("UTF-8-BOM", Encoding::Utf8Bom),
+        ("UTF8BOM", Encoding::Utf8Bom),
+        ("UTF-16", Encoding::Utf16),
+        ("UTF16", Encoding::Utf16),
+        ("UTF-16LE", Encoding::Utf16),
+        ("Unicode", Encoding::Utf16),
+        ("ANSI", Encoding::Ansi),
+    ];
+}
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_BOM: &[u8] = &[0xFF, 0xFE];
+
+/// Convert `text` to the ANSI code page.
+fn encode_ansi(text: &str) -> Vec<u8> {
+    let wide: Vec<u16> = text.encode_utf16().collect();
+    if wide.is_empty() {
+        return Vec::new();
+    }
+    let len = unsafe { WideCharToMultiByte(CP_ACP, 0, &wide, None, PCSTR::null(), None) };
+    let mut bytes = vec![0u8; len.max(0) as usize];
+    let written =
+        unsafe { WideCharToMultiByte(CP_ACP, 0, &wide, Some(&mut bytes), PCSTR::null(), None) };
+    bytes.truncate(written.max(0) as usize);
+    bytes
+}
+
+impl Encoding {
+    /// Encode `text`, starting with the byte order mark if the encoding has
+    /// one.
+    ///
+    /// ```rust
+    /// # use rainmeter::Encoding;
+    /// assert_eq!(Encoding::Utf8.encode("é"), [0xC3, 0xA9]);
+    /// assert_eq!(Encoding::Utf8Bom.encode("é"), [0xEF, 0xBB, 0xBF, 0xC3, 0xA9]);
+    /// assert_eq!(Encoding::Utf16.encode("é"), [0xFF, 0xFE, 0xE9, 0x00]);
+    /// ```
+    pub fn encode(self, text: &str) -> Vec<u8> {
+        match self {
+            Encoding::Utf8 => text.as_bytes().to_vec(),
+            Encoding::Utf8Bom => [UTF8_BOM, text.as_bytes()].concat(),
+            Encoding::Utf16 => UTF16_BOM
+                .iter()
+                .copied()
+                .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+                .collect(),
+            Encoding::Ansi => encode_ansi(text),
+        }
+    }
+}
+
+impl RainmeterContext {
+    /// Read an encoding option such as `Encoding=UTF-16`. Accepts `UTF-8`,
+    /// `UTF-8-BOM`, `UTF-16` (or `Unicode`) and `ANSI`, ignoring case.
+    /// Unknown values are logged and give `default`.
+    pub fn read_encoding(&self, key: &str, default: Encoding) -> Encoding {
+        self.read_enum(key, default)
+    }
+
+    /// Write `text` to the file at `path` (resolved relative to the skin) in
+    /// `encoding`, replacing the file if it exists. Line endings are
+    /// written as given.
+    ///
+    /// ```rust,ignore
+    /// let encoding = rm.read_encoding("Encoding", Encoding::Utf8);
+    /// rm.write_text(&rm.read_string("ExportFile", "export.csv"), &csv, encoding)?;
+    /// ```
+    pub fn write_text(&self, path: &str, text: &str, encoding: Encoding) -> Result<(), RmError> {
+        let path = self.path_to_absolute(path);
+        std::fs::write(&path, encoding.encode(text))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn encodes_with_and_without_bom() {
+        assert_eq!(Encoding::Utf8.encode(""), b"");
+        assert_eq!(Encoding::Utf8Bom.encode(""), UTF8_BOM);
+        assert_eq!(Encoding::Utf16.encode(""), UTF16_BOM);
+        assert_eq!(Encoding::Utf16.encode("€"), [0xFF, 0xFE, 0xAC, 0x20]);
+        // Plain ASCII is the same in every ANSI code page.
+        assert_eq!(Encoding::Ansi.encode("a,b\r\n"), b"a,b\r\n");
+        assert_eq!(Encoding::Ansi.encode(""), b"");
+    }
+
+    #[test]
+    fn reads_encoding_names() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_encoding("Encoding", Encoding::Utf8), Encoding::Utf8);
+        for (value, expected) in [
+            ("utf-8-bom", Encoding::Utf8Bom),
+            ("Unicode", Encoding::Utf16),
+            ("UTF-32", Encoding::Utf8),
+            ("ansi", Encoding::Ansi),
+        ] {
+            rm.set("Encoding", value);
+            assert_eq!(rm.read_encoding("Encoding", Encoding::Utf8), expected);
+        }
+        assert_eq!(rm.warnings().len(), 1);
+    }
+
+    #[test]
+    fn writes_files_relative_to_the_skin() {
+        let dir =
+            std::env::temp_dir().join(format!("rainmeter-rs-encoding-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let rm = MockContext::new();
+        rm.set_skin_path(&dir);
+
+        rm.write_text("export.txt", "é\n", Encoding::Utf16).unwrap();
+        let path = dir.join("export.txt");
+        assert_eq!(
+            std::fs::read(&path).unwrap(),
+            [0xFF, 0xFE, 0xE9, 0, b'\n', 0]
+        );
+        rm.write_text("export.txt", "é", Encoding::Utf8Bom).unwrap();
+        assert_eq!(
+            std::fs::read(&path).unwrap(),
+            [0xEF, 0xBB, 0xBF, 0xC3, 0xA9]
+        );
+
+        assert!(
+            rm.write_text("missing/export.txt", "", Encoding::Utf8)
+                .is_err()
+        );
+    }
+}
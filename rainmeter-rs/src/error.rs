@@ -0,0 +1,58 @@
+use std::fmt;
+
+/// Errors returned by the fallible helpers in this crate.
+#[derive(Debug)]
+pub enum RmError {
+    /// An option was present but its value could not be parsed.
+    ParseFailed { key: String, message: String },
+    /// Persisted plugin state could not be decoded or migrated.
+    InvalidState(String),
+    /// A Windows API call failed.
+    Win32(windows::core::Error),
+    /// A file or process operation failed.
+    Io(std::io::Error),
+    /// An HTTP request got a response with this non-success status code.
+    HttpStatus(u32),
+    /// A user script failed to compile or run.
+    Script(String),
+}
+
+impl fmt::Display for RmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RmError::ParseFailed { key, message } => {
+                write!(f, "Failed to parse option '{}': {}", key, message)
+            }
+            RmError::InvalidState(message) => write!(f, "Invalid plugin state: {}", message),
+            RmError::Win32(err) => write!(f, "Windows API error: {}", err),
+            RmError::Io(err) => write!(f, "I/O error: {}", err),
+            RmError::HttpStatus(status) => write!(f, "HTTP request failed with status {}", status),
+            RmError::Script(message) => write!(f, "Script error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for RmError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RmError::ParseFailed { .. }
+            | RmError::InvalidState(_)
+            | RmError::HttpStatus(_)
+            | RmError::Script(_) => None,
+            RmError::Win32(err) => Some(err),
+            RmError::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<windows::core::Error> for RmError {
+    fn from(err: windows::core::Error) -> Self {
+        RmError::Win32(err)
+    }
+}
+
+impl From<std::io::Error> for RmError {
+    fn from(err: std::io::Error) -> Self {
+        RmError::Io(err)
+    }
+}
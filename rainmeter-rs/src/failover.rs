@@ -0,0 +1,126 @@
+#[cfg(feature = "url")]
+use crate::RainmeterContext;
+
+/// Picks among redundant endpoints (mirrors, fallback servers): stay on the
+/// current one while it works, move to the next one when it fails.
+///
+/// Success goes back to the first entry, so a preferred primary is used
+/// again as soon as it's reachable.
+///
+/// ```rust
+/// # use rainmeter::Failover;
+/// let mut servers = Failover::new(vec!["primary", "mirror"]);
+/// assert_eq!(servers.current(), Some(&"primary"));
+/// assert_eq!(servers.fail(), Some(&"mirror"));
+/// servers.succeed();
+/// assert_eq!(servers.current(), Some(&"primary"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Failover<T> {
+    items: Vec<T>,
+    index: usize,
+    failures: usize,
+}
+
+impl<T> Failover<T> {
+    /// Fail over between `items`, in order of preference.
+    pub fn new(items: Vec<T>) -> Self {
+        Self {
+            items,
+            index: 0,
+            failures: 0,
+        }
+    }
+
+    /// The entry to use next, or `None` if there are no entries.
+    pub fn current(&self) -> Option<&T> {
+        self.items.get(self.index)
+    }
+
+    /// Record that the current entry failed and move to the next one,
+    /// wrapping around after the last. Returns the new current entry.
+    pub fn fail(&mut self) -> Option<&T> {
+        if !self.items.is_empty() {
+            self.index = (self.index + 1) % self.items.len();
+            self.failures += 1;
+        }
+        self.current()
+    }
+
+    /// Record that the current entry worked: go back to the first entry and
+    /// clear the failure count.
+    pub fn succeed(&mut self) {
+        self.index = 0;
+        self.failures = 0;
+    }
+
+    /// Failures since the last success.
+    pub fn consecutive_failures(&self) -> usize {
+        self.failures
+    }
+
+    /// Whether every entry has failed since the last success, i.e. a full
+    /// round found nothing working. Useful to back off before retrying.
+    pub fn all_failed(&self) -> bool {
+        !self.items.is_empty() && self.failures >= self.items.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(feature = "url")]
+impl RainmeterContext {
+    /// Read a `|`-separated list of URLs, e.g.
+    /// `URLs=https://a.example/api | https://b.example/api`. Entries that
+    /// aren't absolute URLs are logged and skipped. Pair with [`Failover`].
+    pub fn read_url_list(&self, key: &str) -> Vec<url::Url> {
+        self.read_list_schema(key, '|', |entry| url::Url::parse(entry).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_through_entries() {
+        let mut servers = Failover::new(vec!["a", "b", "c"]);
+        assert_eq!(servers.fail(), Some(&"b"));
+        assert_eq!(servers.fail(), Some(&"c"));
+        assert!(!servers.all_failed());
+        assert_eq!(servers.fail(), Some(&"a"));
+        assert!(servers.all_failed());
+        assert_eq!(servers.consecutive_failures(), 3);
+        servers.fail();
+        servers.succeed();
+        assert_eq!(servers.current(), Some(&"a"));
+        assert_eq!(servers.consecutive_failures(), 0);
+
+        let mut empty = Failover::<&str>::new(Vec::new());
+        assert_eq!(empty.fail(), None);
+        assert!(!empty.all_failed());
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn reads_url_lists() {
+        let rm = crate::mock::MockContext::new();
+        rm.set(
+            "URLs",
+            "https://a.example/api | a.example/api | http://b.example:8080/ | ",
+        );
+        let urls = rm.read_url_list("URLs");
+        let urls: Vec<&str> = urls.iter().map(url::Url::as_str).collect();
+        assert_eq!(urls, ["https://a.example/api", "http://b.example:8080/"]);
+        assert_eq!(
+            rm.warnings(),
+            ["URLs: skipping invalid entry 'a.example/api'".to_string()]
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{RainmeterContext, RmLogLevel};
+use std::ops::Deref;
+
+/// The option that makes [`read_feature_set`](RainmeterContext::read_feature_set)
+/// turn on missing dependencies instead of turning off the features that
+/// need them.
+const AUTO_ENABLE_OPTION: &str = "AutoEnableDependencies";
+
+/// The features enabled by an option such as `Features=Fetch | Cache`.
+///
+/// Derefs to the underlying `Vec` of names, in the order given (followed by
+/// any dependencies that were enabled automatically).
+///
+/// ```rust
+/// # use rainmeter::FeatureSet;
+/// let features = FeatureSet::new(vec!["Fetch".into(), "Cache".into()]);
+/// assert!(features.is_enabled("cache"));
+/// assert!(!features.is_enabled("Notify"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureSet {
+    enabled: Vec<String>,
+}
+
+impl FeatureSet {
+    pub fn new(enabled: Vec<String>) -> Self {
+        Self { enabled }
+    }
+
+    /// Whether `feature` is enabled, ignoring case.
+    pub fn is_enabled(&self, feature: &str) -> bool {
+        self.enabled.iter().any(|f| f.eq_ignore_ascii_case(feature))
+    }
+}
+
+impl Deref for FeatureSet {
+    type Target = Vec<String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.enabled
+    }
+}
+
+impl RainmeterContext {
+    /// Read a `|`-separated list of enabled features and check it against
+    /// `deps`, a list of `(feature, requires)` pairs such as
+    /// `&[("Cache", "Fetch"), ("Notify", "Fetch")]`. Names ignore case.
+    ///
+    /// Each enabled feature whose requirement isn't enabled is logged as an
+    /// error and turned off, or, if the measure has
+    /// `AutoEnableDependencies=1`, the requirement is turned on instead.
+    /// Chains of dependencies are followed either way.
+    ///
+    /// ```rust,ignore
+    /// // Features=Cache  (with AutoEnableDependencies=1, Fetch is enabled too)
+    /// let features = rm.read_feature_set("Features", &[("Cache", "Fetch")]);
+    /// if features.is_enabled("Cache") { ... }
+    /// ```
+    pub fn read_feature_set(&self, key: &str, deps: &[(&str, &str)]) -> FeatureSet {
+        let auto_enable = self.read_bool(AUTO_ENABLE_OPTION, false);
+        let mut features = FeatureSet::new(Vec::new());
+        for feature in self.read_string_list(key, '|') {
+            if !features.is_enabled(&feature) {
+                features.enabled.push(feature);
+            }
+        }
+        // Each pass enables or disables at least one feature, so this ends.
+        while let Some(&(feature, requires)) = deps.iter().find(|(feature, requires)| {
+            features.is_enabled(feature) && !features.is_enabled(requires)
+        }) {
+            if auto_enable {
+                self.log(
+                    RmLogLevel::LogNotice,
+                    &format!(
+                        "{}: enabling '{}', which '{}' requires",
+                        key, requires, feature
+                    ),
+                );
+                features.enabled.push(requires.to_string());
+            } else {
+                self.log(
+                    RmLogLevel::LogError,
+                    &format!(
+                        "{}: '{}' requires '{}', which is not enabled; disabling '{}'",
+                        key, feature, requires, feature
+                    ),
+                );
+                features
+                    .enabled
+                    .retain(|f| !f.eq_ignore_ascii_case(feature));
+            }
+        }
+        features
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    const DEPS: &[(&str, &str)] = &[("Cache", "Fetch"), ("Notify", "Cache")];
+
+    #[test]
+    fn features_missing_a_dependency_are_disabled() {
+        let rm = MockContext::new();
+        rm.set("Features", "notify | Cache | Extra | cache");
+        let features = rm.read_feature_set("Features", DEPS);
+        // Disabling `Cache` takes `Notify` down with it.
+        assert_eq!(features[..], ["Extra"]);
+        assert_eq!(
+            rm.warnings(),
+            [
+                "Features: 'Cache' requires 'Fetch', which is not enabled; disabling 'Cache'"
+                    .to_string(),
+                "Features: 'Notify' requires 'Cache', which is not enabled; disabling 'Notify'"
+                    .to_string(),
+            ]
+        );
+        assert!(
+            rm.logs()
+                .iter()
+                .all(|(level, _)| *level == RmLogLevel::LogError as i32)
+        );
+    }
+
+    #[test]
+    fn dependencies_can_be_enabled_automatically() {
+        let rm = MockContext::new();
+        rm.set("Features", "Notify").set(AUTO_ENABLE_OPTION, "1");
+        let features = rm.read_feature_set("Features", DEPS);
+        assert_eq!(features[..], ["Notify", "Cache", "Fetch"]);
+        assert!(rm.warnings().is_empty());
+        assert_eq!(rm.logs().len(), 2);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::options::lookup_mapped;
+use crate::{RainmeterContext, RmLogLevel};
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Graphics::Gdi::{
+    DEFAULT_CHARSET, EnumFontFamiliesExW, GetDC, LOGFONTW, ReleaseDC, TEXTMETRICW,
+};
+
+/// Font weight/slant combinations accepted in a font spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FontStyle {
+    #[default]
+    Normal,
+    Bold,
+    Italic,
+    BoldItalic,
+}
+
+const FONT_STYLE_NAMES: &[(&str, FontStyle)] = &[
+    ("Normal", FontStyle::Normal),
+    ("Bold", FontStyle::Bold),
+    ("Italic", FontStyle::Italic),
+    ("BoldItalic", FontStyle::BoldItalic),
+];
+
+/// A parsed `family:size:style` font spec, e.g. `Segoe UI:14:Bold`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontSpec {
+    pub family: String,
+    pub size: f64,
+    pub style: FontStyle,
+}
+
+impl Default for FontSpec {
+    fn default() -> Self {
+        Self {
+            family: "Arial".to_string(),
+            size: 10.0,
+            style: FontStyle::Normal,
+        }
+    }
+}
+
+impl RainmeterContext {
+    /// Read a `family:size:style` font spec. Any field may be left out or
+    /// empty (`Segoe UI`, `:14`, `::Bold`) and is then taken from `default`.
+    /// Invalid sizes or styles are logged and replaced by the default's.
+    pub fn read_font(&self, key: &str, default: FontSpec) -> FontSpec {
+        let value = self.read_string(key, "");
+        let mut parts = value.splitn(3, ':').map(str::trim);
+        let mut font = default;
+
+        if let Some(family) = parts.next().filter(|f| !f.is_empty()) {
+            font.family = family.to_string();
+        }
+        if let Some(size) = parts.next().filter(|s| !s.is_empty()) {
+            match size.parse::<f64>() {
+                Ok(size) if size > 0.0 => font.size = size,
+                _ => self.log(
+                    RmLogLevel::LogWarning,
+                    &format!("{}: invalid font size '{}'", key, size),
+                ),
+            }
+        }
+        if let Some(style) = parts.next().filter(|s| !s.is_empty()) {
+            match lookup_mapped(style, FONT_STYLE_NAMES) {
+                Some(style) => font.style = style,
+                None => self.log(
+                    RmLogLevel::LogWarning,
+                    &format!("{}: unknown font style '{}'", key, style),
+                ),
+            }
+        }
+        font
+    }
+
+    /// Read a `|`-separated list of font families in order of preference,
+    /// e.g. `FontFallbacks=Segoe UI Variable | Segoe UI | Arial`.
+    ///
+    /// Pick the first one that's installed with [`font_exists`]:
+    ///
+    /// ```rust,ignore
+    /// let family = rm
+    ///     .read_font_fallbacks("FontFallbacks")
+    ///     .into_iter()
+    ///     .find(|name| rainmeter::font_exists(name))
+    ///     .unwrap_or_else(|| "Arial".to_string());
+    /// ```
+    pub fn read_font_fallbacks(&self, key: &str) -> Vec<String> {
+        self.read_string_list(key, '|')
+    }
+}
+
+/// Whether a font family named `name` is installed (case-insensitive).
+///
+/// Only real family names match; CSS-style generic names like `sans-serif`
+/// don't exist on Windows and always return `false`.
+pub fn font_exists(name: &str) -> bool {
+    let mut logfont = LOGFONTW {
+        lfCharSet: DEFAULT_CHARSET,
+        ..Default::default()
+    };
+    let wide: Vec<u16> = name.trim().encode_utf16().collect();
+    // GDI face names are limited to 31 characters plus the terminator.
+    if wide.is_empty() || wide.len() >= logfont.lfFaceName.len() {
+        return false;
+    }
+    logfont.lfFaceName[..wide.len()].copy_from_slice(&wide);
+
+    unsafe extern "system" fn found(
+        _logfont: *const LOGFONTW,
+        _metrics: *const TEXTMETRICW,
+        _font_type: u32,
+        lparam: LPARAM,
+    ) -> i32 {
+        unsafe { *(lparam.0 as *mut bool) = true };
+        0 // one match is enough; stop enumerating
+    }
+
+    let mut exists = false;
+    unsafe {
+        let hdc = GetDC(None);
+        EnumFontFamiliesExW(
+            hdc,
+            &logfont,
+            Some(found),
+            LPARAM(&mut exists as *mut bool as isize),
+            0,
+        );
+        ReleaseDC(None, hdc);
+    }
+    exists
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    fn spec(family: &str, size: f64, style: FontStyle) -> FontSpec {
+        FontSpec {
+            family: family.to_string(),
+            size,
+            style,
+        }
+    }
+
+    #[test]
+    fn reads_partial_font_specs() {
+        let rm = MockContext::new();
+        assert_eq!(
+            rm.read_font("Font", FontSpec::default()),
+            FontSpec::default()
+        );
+        for (value, expected) in [
+            ("Segoe UI", spec("Segoe UI", 10.0, FontStyle::Normal)),
+            ("Segoe UI:14", spec("Segoe UI", 14.0, FontStyle::Normal)),
+            (
+                " Consolas : 9.5 : bolditalic ",
+                spec("Consolas", 9.5, FontStyle::BoldItalic),
+            ),
+            (":14", spec("Arial", 14.0, FontStyle::Normal)),
+            ("::Bold", spec("Arial", 10.0, FontStyle::Bold)),
+        ] {
+            rm.set("Font", value);
+            assert_eq!(
+                rm.read_font("Font", FontSpec::default()),
+                expected,
+                "{}",
+                value
+            );
+        }
+        assert!(rm.warnings().is_empty());
+    }
+
+    #[test]
+    fn invalid_fields_fall_back() {
+        let rm = MockContext::new();
+        rm.set("Font", "Segoe UI:big:Heavy");
+        assert_eq!(
+            rm.read_font("Font", FontSpec::default()),
+            spec("Segoe UI", 10.0, FontStyle::Normal)
+        );
+        rm.set("Font", "Segoe UI:-2");
+        assert_eq!(rm.read_font("Font", FontSpec::default()).size, 10.0);
+        assert_eq!(
+            rm.warnings(),
+            [
+                "Font: invalid font size 'big'",
+                "Font: unknown font style 'Heavy'",
+                "Font: invalid font size '-2'",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_font_fallbacks() {
+        let rm = MockContext::new();
+        assert!(rm.read_font_fallbacks("FontFallbacks").is_empty());
+        rm.set("FontFallbacks", " Segoe UI Variable | | Segoe UI|Arial ");
+        assert_eq!(
+            rm.read_font_fallbacks("FontFallbacks"),
+            ["Segoe UI Variable", "Segoe UI", "Arial"]
+        );
+    }
+
+    #[test]
+    fn finds_installed_fonts() {
+        assert!(font_exists("Arial"));
+        assert!(font_exists(" segoe ui "));
+        assert!(!font_exists("No Such Font 0xDEADBEEF"));
+        assert!(!font_exists(""));
+        assert!(!font_exists(&"A".repeat(40)));
+    }
+}
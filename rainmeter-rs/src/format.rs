@@ -0,0 +1,292 @@
+//! Helpers for formatting measure values for `get_string`.
+
+use crate::{RainmeterContext, RmLogLevel};
+
+/// Most decimal places [`read_precision`](RainmeterContext::read_precision)
+/// and the `round:n` pipeline stage accept; an `f64` has no more than 15
+/// significant decimal digits.
+pub(crate) const MAX_PRECISION: usize = 15;
+
+/// Output styles for [`format_duration`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationStyle {
+    /// `HH:MM:SS`; hours keep counting past 24 (`25:01:01`).
+    Clock,
+    /// Largest-first non-zero units, e.g. `1d 1h 1m 1s` or `0s`.
+    Human,
+}
+
+/// Format a number of seconds as a duration. Fractional seconds are truncated
+/// and negative durations get a leading `-`.
+pub fn format_duration(secs: f64, style: DurationStyle) -> String {
+    let sign = if secs < 0.0 { "-" } else { "" };
+    // `as` saturates, and maps NaN to 0.
+    let total = secs.abs() as u64;
+    let (days, hours, minutes, seconds) = (
+        total / 86_400,
+        total / 3_600 % 24,
+        total / 60 % 60,
+        total % 60,
+    );
+
+    match style {
+        DurationStyle::Clock => {
+            format!("{}{:02}:{:02}:{:02}", sign, total / 3_600, minutes, seconds)
+        }
+        DurationStyle::Human => {
+            let parts: Vec<String> = [(days, "d"), (hours, "h"), (minutes, "m"), (seconds, "s")]
+                .iter()
+                .filter(|(n, _)| *n > 0)
+                .map(|(n, unit)| format!("{}{}", n, unit))
+                .collect();
+            if parts.is_empty() {
+                "0s".to_string()
+            } else {
+                format!("{}{}", sign, parts.join(" "))
+            }
+        }
+    }
+}
+
+/// [`format_duration`] shaped for returning straight from
+/// `RainmeterPlugin::get_string`: non-finite values yield `None`, so Rainmeter
+/// falls back to showing the number.
+///
+/// ```rust,ignore
+/// fn get_string(&mut self, _rm: RainmeterContext) -> Option<String> {
+///     duration_string(self.elapsed, DurationStyle::Human)
+/// }
+/// ```
+pub fn duration_string(secs: f64, style: DurationStyle) -> Option<String> {
+    secs.is_finite().then(|| format_duration(secs, style))
+}
+
+/// Shorten `s` to at most `max_chars` characters, replacing the tail with
+/// `…` if anything was cut. Counts `char`s, so multibyte text is never split
+/// mid-character (combining marks can still be separated from their base).
+///
+/// ```rust
+/// # use rainmeter::truncate_ellipsis;
+/// assert_eq!(truncate_ellipsis("Rainmeter", 5), "Rain…");
+/// assert_eq!(truncate_ellipsis("Rain", 5), "Rain");
+/// ```
+pub fn truncate_ellipsis(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        None => s.to_string(),
+        Some(_) if max_chars == 0 => String::new(),
+        Some(_) => {
+            let (cut, _) = s.char_indices().nth(max_chars - 1).unwrap();
+            format!("{}…", &s[..cut])
+        }
+    }
+}
+
+/// Format `value` with `precision` decimal places. With `trim_zeros`,
+/// trailing zeros after the decimal point are dropped, along with the point
+/// itself if nothing is left (`2.50` becomes `2.5`, `3.00` becomes `3`).
+///
+/// ```rust
+/// # use rainmeter::format_value;
+/// assert_eq!(format_value(1.23456, 2, false), "1.23");
+/// assert_eq!(format_value(2.5, 3, true), "2.5");
+/// ```
+pub fn format_value(value: f64, precision: usize, trim_zeros: bool) -> String {
+    let mut formatted = format!("{:.*}", precision, value);
+    // Small negatives round to "-0.00"; a signed zero is just noise on a skin.
+    if formatted.starts_with('-') && formatted.bytes().all(|b| matches!(b, b'-' | b'0' | b'.')) {
+        formatted.remove(0);
+    }
+    if !trim_zeros || !formatted.contains('.') {
+        return formatted;
+    }
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// A label with singular and plural forms, e.g. `{count} item` and
+/// `{count} items`, for displays like "1 item" / "3 items".
+///
+/// English rules: the singular form is used for a count of 1 (or -1), the
+/// plural for everything else, including 0.
+///
+/// ```rust
+/// # use rainmeter::PluralTemplate;
+/// let label = PluralTemplate::new("{count} update", "{count} updates");
+/// assert_eq!(label.render(0), "0 updates");
+/// assert_eq!(label.render(1), "1 update");
+/// assert_eq!(label.render(2), "2 updates");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluralTemplate {
+    singular: String,
+    plural: String,
+}
+
+impl PluralTemplate {
+    pub fn new(singular: impl Into<String>, plural: impl Into<String>) -> Self {
+        Self {
+            singular: singular.into(),
+            plural: plural.into(),
+        }
+    }
+
+    /// Pick the form for `count` and replace every `{count}` in it.
+    pub fn render(&self, count: i64) -> String {
+        let form = if count.unsigned_abs() == 1 {
+            &self.singular
+        } else {
+            &self.plural
+        };
+        form.replace("{count}", &count.to_string())
+    }
+}
+
+/// Quote and escape `s` as a JSON string literal.
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Format `(key, value)` pairs as a compact JSON object of strings.
+pub(crate) fn json_object(fields: &[(&str, &str)]) -> String {
+    let body: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+        .collect();
+    format!("{{{}}}", body.join(","))
+}
+
+impl RainmeterContext {
+    /// Read a number of decimal places for [`format_value`], e.g.
+    /// `Precision=2`. Missing, negative or non-numeric values give `default`;
+    /// values above 15 are logged and clamped to 15.
+    pub fn read_precision(&self, key: &str, default: usize) -> usize {
+        let precision = self.read_formula(key, default as f64);
+        if precision > MAX_PRECISION as f64 {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!(
+                    "{}: {} decimal places is more than {}, clamping",
+                    key, precision, MAX_PRECISION
+                ),
+            );
+            MAX_PRECISION
+        } else if precision >= 0.0 {
+            precision as usize
+        } else {
+            default
+        }
+    }
+
+    /// Read a maximum text length for [`truncate_ellipsis`], e.g.
+    /// `MaxLength=40`. Returns `None` (no limit) if the option is missing,
+    /// zero or negative.
+    pub fn read_max_length(&self, key: &str) -> Option<usize> {
+        let max = self.read_formula(key, 0.0);
+        (max >= 1.0).then_some(max as usize)
+    }
+
+    /// Read a [`PluralTemplate`] from a singular and a plural option, e.g.
+    /// `Label={count} file` and `LabelPlural={count} files`. If only one of
+    /// them is set it is used for both; if neither is, the label is just the
+    /// count.
+    pub fn read_plural_template(&self, singular_key: &str, plural_key: &str) -> PluralTemplate {
+        let singular = self.read_string(singular_key, "");
+        let plural = self.read_string(plural_key, "");
+        match (singular.is_empty(), plural.is_empty()) {
+            (true, true) => PluralTemplate::new("{count}", "{count}"),
+            (false, true) => PluralTemplate::new(singular.clone(), singular),
+            (true, false) => PluralTemplate::new(plural.clone(), plural),
+            (false, false) => PluralTemplate::new(singular, plural),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn formats_with_precision() {
+        assert_eq!(format_value(2.5, 0, false), "2");
+        assert_eq!(format_value(1.23456, 2, false), "1.23");
+        assert_eq!(format_value(3.0, 2, false), "3.00");
+        assert_eq!(format_value(-0.001, 2, false), "0.00");
+    }
+
+    #[test]
+    fn trims_trailing_zeros() {
+        assert_eq!(format_value(2.5, 3, true), "2.5");
+        assert_eq!(format_value(3.0, 2, true), "3");
+        assert_eq!(format_value(120.0, 0, true), "120");
+    }
+
+    #[test]
+    fn reads_and_clamps_precision() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_precision("Precision", 1), 1);
+        rm.set("Precision", "2");
+        assert_eq!(rm.read_precision("Precision", 1), 2);
+        rm.set("Precision", "-1");
+        assert_eq!(rm.read_precision("Precision", 1), 1);
+        assert!(rm.warnings().is_empty());
+
+        rm.set("Precision", "400");
+        assert_eq!(rm.read_precision("Precision", 1), MAX_PRECISION);
+        assert_eq!(rm.warnings().len(), 1);
+    }
+
+    #[test]
+    fn formats_durations_in_both_styles() {
+        for (secs, clock, human) in [
+            (0.0, "00:00:00", "0s"),
+            (61.0, "00:01:01", "1m 1s"),
+            (3661.0, "01:01:01", "1h 1m 1s"),
+            (90061.0, "25:01:01", "1d 1h 1m 1s"),
+        ] {
+            assert_eq!(format_duration(secs, DurationStyle::Clock), clock);
+            assert_eq!(format_duration(secs, DurationStyle::Human), human);
+        }
+    }
+
+    #[test]
+    fn durations_truncate_and_keep_the_sign() {
+        assert_eq!(format_duration(61.9, DurationStyle::Clock), "00:01:01");
+        assert_eq!(format_duration(-61.0, DurationStyle::Clock), "-00:01:01");
+        assert_eq!(format_duration(-3600.0, DurationStyle::Human), "-1h");
+        assert_eq!(duration_string(f64::NAN, DurationStyle::Human), None);
+        assert_eq!(
+            duration_string(60.0, DurationStyle::Human).as_deref(),
+            Some("1m")
+        );
+    }
+
+    #[test]
+    fn truncates_with_an_ellipsis() {
+        assert_eq!(truncate_ellipsis("Rainmeter", 9), "Rainmeter");
+        assert_eq!(truncate_ellipsis("Rainmeter", 8), "Rainmet…");
+        assert_eq!(truncate_ellipsis("Rainmeter", 1), "…");
+        assert_eq!(truncate_ellipsis("Rainmeter", 0), "");
+        assert_eq!(truncate_ellipsis("", 0), "");
+        // Counts chars, not bytes.
+        assert_eq!(truncate_ellipsis("Grüße aus Köln", 6), "Grüße…");
+        assert_eq!(truncate_ellipsis("☀️🌧️⛅", 4), "☀️🌧…");
+        assert_eq!(truncate_ellipsis("🌧🌧🌧", 3), "🌧🌧🌧");
+    }
+}
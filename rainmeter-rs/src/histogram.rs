@@ -0,0 +1,156 @@
+use crate::{RainmeterContext, RmLogLevel};
+
+/// Counts values into buckets between ascending edges, for distribution
+/// displays. Edges `0, 10, 50` give the buckets `[0, 10)` and `[10, 50]`;
+/// the last bucket includes its upper edge.
+///
+/// ```rust
+/// # use rainmeter::Histogram;
+/// let mut histogram = Histogram::new(vec![0.0, 10.0, 50.0, 100.0]);
+/// for v in [3.0, 10.0, 12.0, 100.0, 250.0, -1.0] {
+///     histogram.add(v);
+/// }
+/// assert_eq!(histogram.counts(), [1, 2, 1]);
+/// assert_eq!(histogram.out_of_range(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Histogram {
+    edges: Vec<f64>,
+    counts: Vec<u64>,
+    out_of_range: u64,
+}
+
+impl Histogram {
+    /// Create an empty histogram. `edges` are sorted and repeats dropped
+    /// (as [`read_buckets`](RainmeterContext::read_buckets) already does);
+    /// with fewer than two edges there are no buckets.
+    pub fn new(mut edges: Vec<f64>) -> Self {
+        edges.sort_by(f64::total_cmp);
+        edges.dedup();
+        let buckets = edges.len().saturating_sub(1);
+        Self {
+            edges,
+            counts: vec![0; buckets],
+            out_of_range: 0,
+        }
+    }
+
+    /// The bucket `v` falls in, or `None` outside the edges (or for NaN).
+    pub fn bucket(&self, v: f64) -> Option<usize> {
+        let (first, last) = (*self.edges.first()?, *self.edges.last()?);
+        if self.counts.is_empty() || !(first..=last).contains(&v) {
+            return None;
+        }
+        let above = self.edges.partition_point(|&edge| edge <= v);
+        Some((above - 1).min(self.counts.len() - 1))
+    }
+
+    /// Count `v`. Values outside the edges are only counted in
+    /// [`out_of_range`](Self::out_of_range).
+    pub fn add(&mut self, v: f64) {
+        match self.bucket(v) {
+            Some(index) => self.counts[index] += 1,
+            None => self.out_of_range += 1,
+        }
+    }
+
+    /// The count for each bucket, from the lowest up.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    pub fn edges(&self) -> &[f64] {
+        &self.edges
+    }
+
+    /// How many values fell outside the edges.
+    pub fn out_of_range(&self) -> u64 {
+        self.out_of_range
+    }
+
+    /// All values added so far, in or out of range.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum::<u64>() + self.out_of_range
+    }
+
+    /// Reset all counts to zero, keeping the edges.
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+        self.out_of_range = 0;
+    }
+}
+
+impl RainmeterContext {
+    /// Read comma-separated bucket edges such as `Buckets=0,10,50,100,500`
+    /// for a [`Histogram`]. Invalid numbers are logged and skipped. Edges
+    /// given out of order are logged, then sorted, and repeated edges are
+    /// dropped. Returns an empty list (no buckets) if fewer than two edges
+    /// remain.
+    pub fn read_buckets(&self, key: &str) -> Vec<f64> {
+        let mut edges = self.read_list_schema(key, ',', |entry| {
+            entry.parse::<f64>().ok().filter(|edge| edge.is_finite())
+        });
+        if edges.is_empty() {
+            return edges;
+        }
+        if !edges.is_sorted_by(|a, b| a < b) {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: bucket edges should be in ascending order", key),
+            );
+            edges.sort_by(f64::total_cmp);
+            edges.dedup();
+        }
+        if edges.len() < 2 {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: need at least two bucket edges", key),
+            );
+            edges.clear();
+        }
+        edges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn out_of_order_edges_are_sorted() {
+        let mut histogram = Histogram::new(vec![50.0, 0.0, 10.0, 10.0]);
+        assert_eq!(histogram.edges(), [0.0, 10.0, 50.0]);
+        for v in [3.0, 10.0, 50.0, 51.0] {
+            histogram.add(v);
+        }
+        assert_eq!(histogram.counts(), [1, 2]);
+        assert_eq!(histogram.out_of_range(), 1);
+    }
+
+    #[test]
+    fn reads_buckets() {
+        let rm = MockContext::new();
+        rm.set("Buckets", "0, 10,50");
+        assert_eq!(rm.read_buckets("Buckets"), [0.0, 10.0, 50.0]);
+        assert!(rm.warnings().is_empty());
+
+        rm.set("Buckets", "100,0,10,10");
+        assert_eq!(rm.read_buckets("Buckets"), [0.0, 10.0, 100.0]);
+        assert_eq!(
+            rm.warnings(),
+            ["Buckets: bucket edges should be in ascending order"]
+        );
+
+        rm.clear_logs();
+        rm.set("Buckets", "5,x");
+        assert!(rm.read_buckets("Buckets").is_empty());
+        assert_eq!(
+            rm.warnings(),
+            [
+                "Buckets: skipping invalid entry 'x'",
+                "Buckets: need at least two bucket edges"
+            ]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use crate::RainmeterContext;
+use std::collections::VecDeque;
+
+/// Rolling window of the most recent values, e.g. for drawing a sparkline.
+/// Once full, each `push` drops the oldest value.
+///
+/// ```rust
+/// # use rainmeter::History;
+/// let mut history = History::new(3);
+/// for v in [1.0, 2.0, 3.0, 4.0] {
+///     history.push(v);
+/// }
+/// assert_eq!(history.iter().collect::<Vec<_>>(), [2.0, 3.0, 4.0]);
+/// assert_eq!(history.avg(), Some(3.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct History {
+    values: VecDeque<f64>,
+    capacity: usize,
+}
+
+impl History {
+    /// Create an empty history holding up to `capacity` values (at least one).
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Create a history sized from the measure's `HistorySize` option.
+    pub fn from_history_size(rm: &RainmeterContext, default: usize) -> Self {
+        let size = rm.read_formula("HistorySize", default as f64);
+        Self::new(if size.is_finite() && size >= 1.0 {
+            size as usize
+        } else {
+            default
+        })
+    }
+
+    pub fn push(&mut self, value: f64) {
+        if self.values.len() == self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    /// Values from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = f64> + '_ {
+        self.values.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    /// Smallest value, or `None` if empty.
+    pub fn min(&self) -> Option<f64> {
+        self.iter().reduce(f64::min)
+    }
+
+    /// Largest value, or `None` if empty.
+    pub fn max(&self) -> Option<f64> {
+        self.iter().reduce(f64::max)
+    }
+
+    /// Mean of the values, or `None` if empty.
+    pub fn avg(&self) -> Option<f64> {
+        if self.values.is_empty() {
+            return None;
+        }
+        Some(self.iter().sum::<f64>() / self.values.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn min_max_and_avg() {
+        let mut history = History::new(4);
+        assert_eq!(
+            (history.min(), history.max(), history.avg()),
+            (None, None, None)
+        );
+        for v in [3.0, -1.0, 7.0, 2.0, 5.0] {
+            history.push(v);
+        }
+        // 3.0 has been dropped.
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.min(), Some(-1.0));
+        assert_eq!(history.max(), Some(7.0));
+        assert_eq!(history.avg(), Some(3.25));
+
+        history.clear();
+        assert_eq!(
+            (history.min(), history.max(), history.avg()),
+            (None, None, None)
+        );
+    }
+
+    #[test]
+    fn sized_from_history_size() {
+        let rm = MockContext::new();
+        assert_eq!(History::from_history_size(&rm.context(), 60).capacity(), 60);
+        rm.set("HistorySize", "10");
+        assert_eq!(History::from_history_size(&rm.context(), 60).capacity(), 10);
+        rm.set("HistorySize", "0");
+        assert_eq!(History::from_history_size(&rm.context(), 60).capacity(), 60);
+    }
+}
@@ -0,0 +1,509 @@
+//! Conditional HTTP GETs over WinHTTP, behind the `http` feature.
+
+use crate::RmError;
+use std::ffi::c_void;
+use std::sync::mpsc::{self, SendError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use windows::Win32::Networking::WinHttp::{
+    URL_COMPONENTS, WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY, WINHTTP_ADDREQ_FLAG_ADD,
+    WINHTTP_FLAG_SECURE, WINHTTP_OPEN_REQUEST_FLAGS, WINHTTP_QUERY_ETAG, WINHTTP_QUERY_FLAG_NUMBER,
+    WINHTTP_QUERY_LAST_MODIFIED, WINHTTP_QUERY_STATUS_CODE, WinHttpAddRequestHeaders,
+    WinHttpCloseHandle, WinHttpConnect, WinHttpCrackUrl, WinHttpOpen, WinHttpOpenRequest,
+    WinHttpQueryHeaders, WinHttpReadData, WinHttpReceiveResponse, WinHttpSendRequest,
+};
+use windows::core::{HSTRING, PCWSTR, w};
+
+/// `INTERNET_SCHEME_HTTPS`, as reported in `URL_COMPONENTS::nScheme`.
+const SCHEME_HTTPS: i32 = 2;
+
+/// Closes a WinHTTP handle when dropped.
+struct Handle(*mut c_void);
+
+impl Handle {
+    fn new(raw: *mut c_void) -> Result<Self, RmError> {
+        if raw.is_null() {
+            Err(windows::core::Error::from_thread().into())
+        } else {
+            Ok(Self(raw))
+        }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        let _ = unsafe { WinHttpCloseHandle(self.0) };
+    }
+}
+
+enum Response {
+    NotModified,
+    Body {
+        body: Vec<u8>,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+/// Copy the `len` UTF-16 units at `ptr` (part of a cracked URL) into a `String`.
+unsafe fn url_part(ptr: windows::core::PWSTR, len: u32) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(ptr.0, len as usize) })
+}
+
+unsafe fn query_header(request: &Handle, info_level: u32) -> Option<String> {
+    let mut size = 0u32;
+    // Fails with ERROR_INSUFFICIENT_BUFFER, setting `size` in bytes.
+    let _ = unsafe {
+        WinHttpQueryHeaders(
+            request.0,
+            info_level,
+            PCWSTR::null(),
+            None,
+            &mut size,
+            std::ptr::null_mut(),
+        )
+    };
+    if size == 0 {
+        return None;
+    }
+    let mut buf = vec![0u16; size as usize / 2 + 1];
+    unsafe {
+        WinHttpQueryHeaders(
+            request.0,
+            info_level,
+            PCWSTR::null(),
+            Some(buf.as_mut_ptr().cast()),
+            &mut size,
+            std::ptr::null_mut(),
+        )
+    }
+    .ok()?;
+    buf.truncate(size as usize / 2);
+    Some(String::from_utf16_lossy(&buf))
+}
+
+fn get(url: &str, etag: Option<&str>, last_modified: Option<&str>) -> Result<Response, RmError> {
+    let wide_url: Vec<u16> = url.encode_utf16().collect();
+    let mut parts = URL_COMPONENTS {
+        dwStructSize: std::mem::size_of::<URL_COMPONENTS>() as u32,
+        // Non-zero lengths with null pointers ask for pointers into `wide_url`.
+        dwHostNameLength: u32::MAX,
+        dwUrlPathLength: u32::MAX,
+        dwExtraInfoLength: u32::MAX,
+        ..Default::default()
+    };
+    unsafe { WinHttpCrackUrl(&wide_url, 0, &mut parts) }?;
+    let host = unsafe { url_part(parts.lpszHostName, parts.dwHostNameLength) };
+    let object = unsafe {
+        url_part(parts.lpszUrlPath, parts.dwUrlPathLength)
+            + &url_part(parts.lpszExtraInfo, parts.dwExtraInfoLength)
+    };
+    let flags = if parts.nScheme.0 == SCHEME_HTTPS {
+        WINHTTP_FLAG_SECURE
+    } else {
+        WINHTTP_OPEN_REQUEST_FLAGS::default()
+    };
+
+    unsafe {
+        let session = Handle::new(WinHttpOpen(
+            w!("rainmeter-rs"),
+            WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY,
+            PCWSTR::null(),
+            PCWSTR::null(),
+            0,
+        ))?;
+        let connection = Handle::new(WinHttpConnect(
+            session.0,
+            &HSTRING::from(host),
+            parts.nPort,
+            0,
+        ))?;
+        let request = Handle::new(WinHttpOpenRequest(
+            connection.0,
+            w!("GET"),
+            &HSTRING::from(object),
+            PCWSTR::null(),
+            PCWSTR::null(),
+            std::ptr::null(),
+            flags,
+        ))?;
+
+        let mut headers = String::new();
+        if let Some(etag) = etag {
+            headers.push_str(&format!("If-None-Match: {}\r\n", etag));
+        }
+        if let Some(last_modified) = last_modified {
+            headers.push_str(&format!("If-Modified-Since: {}\r\n", last_modified));
+        }
+        if !headers.is_empty() {
+            let wide: Vec<u16> = headers.encode_utf16().collect();
+            WinHttpAddRequestHeaders(request.0, &wide, WINHTTP_ADDREQ_FLAG_ADD)?;
+        }
+
+        WinHttpSendRequest(request.0, None, None, 0, 0, 0)?;
+        WinHttpReceiveResponse(request.0, std::ptr::null_mut())?;
+
+        let mut status = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        WinHttpQueryHeaders(
+            request.0,
+            WINHTTP_QUERY_STATUS_CODE | WINHTTP_QUERY_FLAG_NUMBER,
+            PCWSTR::null(),
+            Some((&mut status as *mut u32).cast()),
+            &mut size,
+            std::ptr::null_mut(),
+        )?;
+        match status {
+            304 => return Ok(Response::NotModified),
+            200..=299 => {}
+            _ => return Err(RmError::HttpStatus(status)),
+        }
+
+        let mut body = Vec::new();
+        let mut chunk = vec![0u8; 16 * 1024];
+        loop {
+            let mut read = 0u32;
+            WinHttpReadData(
+                request.0,
+                chunk.as_mut_ptr().cast(),
+                chunk.len() as u32,
+                &mut read,
+            )?;
+            if read == 0 {
+                break;
+            }
+            body.extend_from_slice(&chunk[..read as usize]);
+        }
+        Ok(Response::Body {
+            body,
+            etag: query_header(&request, WINHTTP_QUERY_ETAG),
+            last_modified: query_header(&request, WINHTTP_QUERY_LAST_MODIFIED),
+        })
+    }
+}
+
+/// Signature of [`get`], replaceable in tests.
+type Getter = fn(&str, Option<&str>, Option<&str>) -> Result<Response, RmError>;
+
+#[derive(Default)]
+struct CacheState {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: Option<String>,
+    /// Set when `body` changes, cleared by `take_update`.
+    updated: bool,
+    fetching: bool,
+    error: Option<String>,
+}
+
+fn lock(state: &Mutex<CacheState>) -> MutexGuard<'_, CacheState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Fetch `url` with `get` and store the result in `state`.
+fn fetch_into(state: &Mutex<CacheState>, get: Getter, url: &str) -> Result<bool, RmError> {
+    let (etag, last_modified) = {
+        let mut state = lock(state);
+        if state.url != url {
+            // Start over: neither the old validators nor the old body
+            // belong to the new URL.
+            *state = CacheState {
+                url: url.to_string(),
+                fetching: state.fetching,
+                ..CacheState::default()
+            };
+        }
+        (state.etag.clone(), state.last_modified.clone())
+    };
+    let response = get(url, etag.as_deref(), last_modified.as_deref());
+
+    let mut state = lock(state);
+    if state.url != url {
+        // Another fetch switched URLs meanwhile; this result is stale.
+        return Ok(false);
+    }
+    match response {
+        Ok(Response::NotModified) => {
+            state.error = None;
+            Ok(false)
+        }
+        Ok(Response::Body {
+            body,
+            etag,
+            last_modified,
+        }) => {
+            let body = String::from_utf8_lossy(&body).into_owned();
+            let changed = state.body.as_ref() != Some(&body);
+            state.etag = etag;
+            state.last_modified = last_modified;
+            state.body = Some(body);
+            state.updated |= changed;
+            state.error = None;
+            Ok(changed)
+        }
+        Err(err) => {
+            state.error = Some(err.to_string());
+            Err(err)
+        }
+    }
+}
+
+/// Clears `fetching` when dropped, so a panicking fetch doesn't leave the
+/// cache looking busy forever.
+struct FetchingGuard(Arc<Mutex<CacheState>>);
+
+impl Drop for FetchingGuard {
+    fn drop(&mut self) {
+        lock(&self.0).fetching = false;
+    }
+}
+
+/// Start the thread that runs [`HttpCache::refresh`] fetches, one URL at a
+/// time. It exits once every clone of the cache (and so the sender) is gone.
+fn spawn_worker(state: Arc<Mutex<CacheState>>, get: Getter) -> Sender<String> {
+    let (sender, urls) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        // Declared before the loop so that, if a fetch panics, the receiver
+        // is dropped first: `refresh` then sees a dead worker and starts a
+        // new one instead of queueing to this one.
+        let _fetching = FetchingGuard(Arc::clone(&state));
+        for url in urls {
+            let _ = fetch_into(&state, get, &url);
+            lock(&state).fetching = false;
+        }
+    });
+    sender
+}
+
+struct Shared {
+    state: Arc<Mutex<CacheState>>,
+    /// Sends URLs to the worker; started on the first `refresh`.
+    worker: Mutex<Option<Sender<String>>>,
+    get: Getter,
+}
+
+/// Keeps the last response body for a URL and re-downloads it only when the
+/// server says it changed, using `ETag` / `Last-Modified` validators
+/// (`If-None-Match` / `If-Modified-Since`, answered with `304 Not Modified`).
+///
+/// Clones share the same cache. Call [`refresh`](Self::refresh) from
+/// `update` to fetch on the cache's background thread, and
+/// [`take_update`](Self::take_update) to pick up a changed body:
+///
+/// ```rust,ignore
+/// fn update(&mut self, rm: RainmeterContext) -> f64 {
+///     if self.timer.should_refresh(Instant::now()) {
+///         self.cache.refresh(&self.url);
+///     }
+///     if let Some(body) = self.cache.take_update() {
+///         self.parsed = parse(&body);
+///     }
+///     self.parsed.value
+/// }
+/// ```
+#[derive(Clone)]
+pub struct HttpCache {
+    shared: Arc<Shared>,
+}
+
+impl Default for HttpCache {
+    fn default() -> Self {
+        Self::with_getter(get)
+    }
+}
+
+impl HttpCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_getter(get: Getter) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                state: Arc::default(),
+                worker: Mutex::new(None),
+                get,
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheState> {
+        lock(&self.shared.state)
+    }
+
+    /// Fetch `url` on the calling thread, blocking until done. Returns
+    /// whether the cached body changed. Switching to a different URL drops
+    /// the old body and validators, so the first request for it is
+    /// unconditional.
+    pub fn fetch(&self, url: &str) -> Result<bool, RmError> {
+        fetch_into(&self.shared.state, self.shared.get, url)
+    }
+
+    /// Queue a [`fetch`](Self::fetch) on the cache's background thread,
+    /// unless one is already running. Returns whether a fetch was queued.
+    /// Failures are kept for [`last_error`](Self::last_error); the previous
+    /// body stays available.
+    pub fn refresh(&self, url: &str) -> bool {
+        {
+            let mut state = self.lock();
+            if state.fetching {
+                return false;
+            }
+            state.fetching = true;
+        }
+        let mut worker = self.shared.worker.lock().unwrap_or_else(|e| e.into_inner());
+        let url = match worker.as_ref().map(|sender| sender.send(url.to_string())) {
+            Some(Ok(())) => return true,
+            // The worker died in a panicking fetch; start another.
+            Some(Err(SendError(url))) => url,
+            None => url.to_string(),
+        };
+        let sender = spawn_worker(Arc::clone(&self.shared.state), self.shared.get);
+        let _ = sender.send(url);
+        *worker = Some(sender);
+        true
+    }
+    /// The most recently downloaded body, if any.
+    pub fn body(&self) -> Option<String> {
+        self.lock().body.clone()
+    }
+
+    /// The body, but only if it changed since the last call.
+    pub fn take_update(&self) -> Option<String> {
+        let mut state = self.lock();
+        if !std::mem::take(&mut state.updated) {
+            return None;
+        }
+        state.body.clone()
+    }
+
+    pub fn is_fetching(&self) -> bool {
+        self.lock().fetching
+    }
+
+    /// The error from the last fetch, or `None` if it succeeded.
+    pub fn last_error(&self) -> Option<String> {
+        self.lock().error.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+
+    const LAST_MODIFIED: &str = "Wed, 21 Oct 2015 07:28:00 GMT";
+
+    /// Serve `count` requests on a local port: `304` to requests carrying
+    /// the `"v1"` validator, otherwise `200` with a body and validators.
+    /// Returns the base URL and a receiver for the raw request heads.
+    fn serve(count: usize) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/data", listener.local_addr().unwrap());
+        let (sender, requests) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().take(count) {
+                let mut stream = stream.unwrap();
+                let mut head = String::new();
+                let mut reader = BufReader::new(&stream);
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+                let response = if head.contains("If-None-Match: \"v1\"") {
+                    "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nContent-Length: 0\r\n\
+                     Connection: close\r\n\r\n"
+                        .to_string()
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nLast-Modified: {}\r\n\
+                         Content-Length: 5\r\nConnection: close\r\n\r\nhello",
+                        LAST_MODIFIED
+                    )
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+                sender.send(head).unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    fn wait_idle(cache: &HttpCache) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while cache.is_fetching() {
+            assert!(Instant::now() < deadline, "fetch never finished");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn revalidates_with_etag_and_last_modified() {
+        let (url, requests) = serve(3);
+        let cache = HttpCache::new();
+
+        assert!(cache.fetch(&url).unwrap());
+        assert_eq!(cache.take_update().as_deref(), Some("hello"));
+        let first = requests.recv().unwrap();
+        assert!(!first.contains("If-None-Match"));
+
+        assert!(!cache.fetch(&url).unwrap());
+        let second = requests.recv().unwrap();
+        assert!(second.contains("If-None-Match: \"v1\""));
+        assert!(second.contains(&format!("If-Modified-Since: {}", LAST_MODIFIED)));
+        assert_eq!(cache.take_update(), None);
+        assert_eq!(cache.body().as_deref(), Some("hello"));
+
+        // The same through the background worker.
+        assert!(cache.refresh(&url));
+        wait_idle(&cache);
+        assert!(requests.recv().unwrap().contains("If-None-Match"));
+        assert_eq!(cache.last_error(), None);
+        assert_eq!(cache.body().as_deref(), Some("hello"));
+    }
+
+    /// Panics for `panic`, fails for `fail`, and otherwise returns the URL
+    /// as the body.
+    fn fake_get(url: &str, _: Option<&str>, _: Option<&str>) -> Result<Response, RmError> {
+        match url {
+            "panic" => panic!("fetch panicked"),
+            "fail" => Err(RmError::HttpStatus(500)),
+            _ => Ok(Response::Body {
+                body: url.as_bytes().to_vec(),
+                etag: None,
+                last_modified: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn refresh_recovers_from_a_panicking_fetch() {
+        let cache = HttpCache::with_getter(fake_get);
+        assert!(cache.refresh("panic"));
+        wait_idle(&cache);
+
+        assert!(cache.refresh("a"));
+        wait_idle(&cache);
+        assert_eq!(cache.take_update().as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn url_change_drops_the_old_body() {
+        let cache = HttpCache::with_getter(fake_get);
+        cache.fetch("a").unwrap();
+        assert_eq!(cache.body().as_deref(), Some("a"));
+
+        assert!(cache.fetch("fail").is_err());
+        assert_eq!(cache.body(), None);
+        assert_eq!(cache.take_update(), None);
+        assert!(cache.last_error().is_some());
+    }
+}
@@ -0,0 +1,303 @@
+//! Reading skin INI files directly, for the things the plugin API can't
+//! answer (like which options a section actually sets).
+
+use crate::{RainmeterContext, RmError, RmLogLevel};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Options every measure understands, which plugins shouldn't flag as
+/// unknown. Numbered variants (`IfCondition2`, `IfTrueAction3`, ...) are
+/// matched by stripping trailing digits first.
+const GENERAL_MEASURE_OPTIONS: &[&str] = &[
+    "Measure",
+    "Plugin",
+    "MinValue",
+    "MaxValue",
+    "InvertMeasure",
+    "AverageSize",
+    "UpdateDivider",
+    "Disabled",
+    "Paused",
+    "DynamicVariables",
+    "Group",
+    "OnUpdateAction",
+    "OnChangeAction",
+    "IfAboveValue",
+    "IfAboveAction",
+    "IfBelowValue",
+    "IfBelowAction",
+    "IfEqualValue",
+    "IfEqualAction",
+    "IfCondition",
+    "IfTrueAction",
+    "IfFalseAction",
+    "IfConditionMode",
+    "IfMatch",
+    "IfMatchAction",
+    "IfNotMatchAction",
+    "IfMatchMode",
+    "Substitute",
+    "RegExpSubstitute",
+];
+
+/// Read an INI file as text. Rainmeter accepts UTF-16 LE (with BOM) and
+/// UTF-8 (with or without BOM) skins.
+pub(crate) fn read_ini_text(path: &Path) -> Result<String, RmError> {
+    let bytes = std::fs::read(path)?;
+    if let Some(wide) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = wide
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        return Ok(String::from_utf16_lossy(&units));
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    Ok(text.strip_prefix('\u{feff}').unwrap_or(&text).to_string())
+}
+
+/// The `(key, value)` pairs of `section` in INI `text`, in file order.
+/// Section names match case-insensitively, `;` starts a comment line and
+/// values are trimmed. A section that appears more than once is merged.
+pub(crate) fn parse_section(text: &str, section: &str) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    let mut inside = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            inside = name.trim().eq_ignore_ascii_case(section);
+            continue;
+        }
+        if !inside {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            entries.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    entries
+}
+
+/// Number of single-character edits (insertions, deletions, substitutions)
+/// to turn `a` into `b`, ignoring ASCII case.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let b: Vec<char> = b.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+fn is_general_option(key: &str) -> bool {
+    let base = key.trim_end_matches(|c: char| c.is_ascii_digit());
+    GENERAL_MEASURE_OPTIONS
+        .iter()
+        .any(|option| option.eq_ignore_ascii_case(base))
+}
+
+impl RainmeterContext {
+    /// The option names set in this measure's section of the skin file, in
+    /// file order.
+    ///
+    /// This reads `#CURRENTPATH##CURRENTFILE#` directly, so options that
+    /// come from `@Include` files or are set at runtime with `!SetOption`
+    /// aren't included.
+    pub(crate) fn measure_section_keys(&self) -> Result<Vec<String>, RmError> {
+        let file = self
+            .resolve_variable("#CURRENTPATH##CURRENTFILE#")
+            .ok_or_else(|| RmError::InvalidState("skin file path is unavailable".to_string()))?;
+        let text = read_ini_text(Path::new(&file))?;
+        Ok(parse_section(&text, &self.get_measure_name())
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect())
+    }
+
+    /// Warn about options in this measure's section that the plugin doesn't
+    /// know, suggesting the closest expected name when it's a likely typo:
+    ///
+    /// ```text
+    /// Unknown option 'Intervl'; did you mean 'Interval'?
+    /// ```
+    ///
+    /// `expected` lists the plugin's own options; general measure options
+    /// (`UpdateDivider`, `IfCondition2`, ...) are always accepted. Matching
+    /// ignores case, like Rainmeter does. Call it from `reload`; if the skin
+    /// file can't be read, this logs that at debug level and does nothing.
+    pub fn check_option_typos(&self, expected: &[&str]) {
+        let keys = match self.measure_section_keys() {
+            Ok(keys) => keys,
+            Err(err) => {
+                self.log(
+                    RmLogLevel::LogDebug,
+                    &format!("Skipping option check: {}", err),
+                );
+                return;
+            }
+        };
+        for key in keys {
+            if is_general_option(&key)
+                || expected
+                    .iter()
+                    .any(|option| option.eq_ignore_ascii_case(&key))
+            {
+                continue;
+            }
+            // Only suggest names that are plausibly a slip of the keyboard.
+            let max_distance = (key.chars().count() / 3).max(1);
+            let suggestion = expected
+                .iter()
+                .map(|option| (edit_distance(&key, option), option))
+                .filter(|(distance, _)| *distance <= max_distance)
+                .min_by_key(|(distance, _)| *distance);
+            let message = match suggestion {
+                Some((_, option)) => {
+                    format!("Unknown option '{}'; did you mean '{}'?", key, option)
+                }
+                None => format!("Unknown option '{}'", key),
+            };
+            self.log(RmLogLevel::LogWarning, &message);
+        }
+    }
+
+    /// Read the INI file named by a path option and return one of its
+    /// sections as a map, e.g. shared defaults factored out of a large skin:
+    ///
+    /// ```ini
+    /// Include=#@#Shared.inc
+    /// Include=#@#Shared.inc|Network
+    /// ```
+    ///
+    /// The path is resolved relative to the skin. Without a `|Section`
+    /// suffix the file's `[Variables]` section is read. Values are returned
+    /// as written, without expanding variables, and if a key appears more
+    /// than once the last one wins. A missing option, or a file without the
+    /// section, gives an empty map; a file that can't be read is an error.
+    ///
+    /// ```rust,ignore
+    /// let shared = rm.read_include("Include")?;
+    /// let host = rm.read_string("Host", shared.get("Host").map_or("localhost", |h| h.as_str()));
+    /// ```
+    pub fn read_include(&self, key: &str) -> Result<HashMap<String, String>, RmError> {
+        let value = self.read_string(key, "");
+        let (path, section) = value.split_once('|').unwrap_or((&value, ""));
+        let (path, section) = (path.trim(), section.trim());
+        if path.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let section = if section.is_empty() {
+            "Variables"
+        } else {
+            section
+        };
+        let text = read_ini_text(Path::new(&self.path_to_absolute(path)))?;
+        Ok(parse_section(&text, section).into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+    use std::path::PathBuf;
+
+    /// Write `text` to a file in a fresh temp directory and return its path.
+    fn temp_file(name: &str, text: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rainmeter-rs-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    /// A mock measure whose skin file is `path`.
+    fn measure_in(path: &Path) -> MockContext {
+        let rm = MockContext::new();
+        let (dir, file) = (path.parent().unwrap(), path.file_name().unwrap());
+        rm.set_variable("#CURRENTPATH#", &format!("{}/", dir.display()))
+            .set_variable("#CURRENTFILE#", &file.to_string_lossy())
+            .set_skin_path(dir);
+        rm
+    }
+
+    #[test]
+    fn parses_sections_case_insensitively() {
+        let text = "[MeasureTest]\nA=1\n; B=2\n[Other]\nC=3\n[measuretest]\nD = 4 \n";
+        let entries = parse_section(text, "MeasureTest");
+        let expected = [("A", "1"), ("D", "4")].map(|(k, v)| (k.to_string(), v.to_string()));
+        assert_eq!(entries, expected);
+    }
+
+    #[test]
+    fn reads_utf16_skins() {
+        let bytes: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain("[S]\nK=é\n".encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        let path = temp_file("Utf16.ini", "");
+        std::fs::write(&path, bytes).unwrap();
+        assert_eq!(read_ini_text(&path).unwrap(), "[S]\nK=é\n");
+    }
+
+    #[test]
+    fn warns_about_misspelled_options() {
+        let path = temp_file(
+            "Typos.ini",
+            "[MeasureTest]\nMeasure=Plugin\nPlugin=Test\nIntervl=5\nUpdateDivider=2\n\
+             IfCondition2=1\nUrl=x\nColour=red\n",
+        );
+        let rm = measure_in(&path);
+        rm.check_option_typos(&["Interval", "Url", "Format"]);
+        assert_eq!(
+            rm.warnings(),
+            [
+                "Unknown option 'Intervl'; did you mean 'Interval'?",
+                "Unknown option 'Colour'",
+            ]
+        );
+    }
+
+    #[test]
+    fn unreadable_skin_skips_the_check() {
+        let rm = MockContext::new();
+        rm.check_option_typos(&["Interval"]);
+        assert!(rm.warnings().is_empty());
+    }
+
+    #[test]
+    fn reads_include_sections() {
+        let path = temp_file(
+            "Shared.inc",
+            "[Variables]\nHost=example.com\nPort=80\n[Network]\nRetries=3\nRetries=5\n",
+        );
+        let rm = measure_in(&path);
+        assert!(rm.read_include("Include").unwrap().is_empty());
+
+        rm.set("Include", "Shared.inc");
+        let shared = rm.read_include("Include").unwrap();
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared["Host"], "example.com");
+        assert_eq!(shared["Port"], "80");
+
+        rm.set("Include", &format!("{} | network", path.display()));
+        let network = rm.read_include("Include").unwrap();
+        assert_eq!(network.len(), 1);
+        assert_eq!(network["Retries"], "5");
+
+        rm.set("Include", "Shared.inc|Missing");
+        assert!(rm.read_include("Include").unwrap().is_empty());
+        rm.set("Include", "Missing.inc");
+        assert!(rm.read_include("Include").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+//! Extracting values from JSON documents, WebParser-style.
+
+use crate::{RainmeterContext, RmLogLevel};
+use serde_json::Value as JsonValue;
+
+/// Applies JSON Pointers (RFC 6901, e.g. `/data/items/0/name`) to documents.
+pub struct JsonExtract;
+
+impl JsonExtract {
+    /// Look up `pointer` in `doc` and render it for display: strings without
+    /// quotes, `null` as an empty string, anything else as compact JSON.
+    /// Returns `None` if the pointer doesn't match anything.
+    pub fn get(doc: &JsonValue, pointer: &str) -> Option<String> {
+        Some(match doc.pointer(pointer)? {
+            JsonValue::String(s) => s.clone(),
+            JsonValue::Null => String::new(),
+            other => other.to_string(),
+        })
+    }
+}
+
+impl RainmeterContext {
+    /// Read a JSON Pointer option such as `Field=/data/items/0/name`, for use
+    /// with [`JsonExtract::get`]. An empty pointer selects the whole document.
+    /// Pointers not starting with `/` are logged, since they never match.
+    pub fn read_json_pointer(&self, key: &str) -> String {
+        let pointer = self.read_string(key, "").trim().to_string();
+        if !pointer.is_empty() && !pointer.starts_with('/') {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: JSON pointer '{}' must start with '/'", key, pointer),
+            );
+        }
+        pointer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    fn doc() -> JsonValue {
+        serde_json::json!({
+            "data": {
+                "title": "Weather",
+                "items": [{"name": "Mon", "temp": 21.5}, {"name": "Tue", "temp": null}],
+            }
+        })
+    }
+
+    #[test]
+    fn extracts_nested_values_and_array_items() {
+        let doc = doc();
+        assert_eq!(
+            JsonExtract::get(&doc, "/data/title").as_deref(),
+            Some("Weather")
+        );
+        assert_eq!(
+            JsonExtract::get(&doc, "/data/items/1/name").as_deref(),
+            Some("Tue")
+        );
+        assert_eq!(
+            JsonExtract::get(&doc, "/data/items/0/temp").as_deref(),
+            Some("21.5")
+        );
+        assert_eq!(
+            JsonExtract::get(&doc, "/data/items/1/temp").as_deref(),
+            Some("")
+        );
+        assert_eq!(
+            JsonExtract::get(&doc, "/data/items/0").as_deref(),
+            Some(r#"{"name":"Mon","temp":21.5}"#)
+        );
+        assert_eq!(JsonExtract::get(&doc, "/data/items/2"), None);
+        assert_eq!(JsonExtract::get(&doc, "/data/missing"), None);
+    }
+
+    #[test]
+    fn logs_pointers_without_a_leading_slash() {
+        let rm = MockContext::new();
+        rm.set("Field", " /data/title ");
+        assert_eq!(rm.read_json_pointer("Field"), "/data/title");
+        assert!(rm.warnings().is_empty());
+
+        rm.set("Field", "data/title");
+        assert_eq!(rm.read_json_pointer("Field"), "data/title");
+        assert_eq!(rm.warnings().len(), 1);
+    }
+}
@@ -0,0 +1,170 @@
+//! Key chord options such as `Keys=Ctrl+R:refresh | F5:reload`.
+//!
+//! Plugins can't capture key presses themselves: the skin does that with
+//! Rainmeter's own input actions (e.g. a HotKey or Input plugin measure, or
+//! mouse actions) and passes the chord to the plugin, typically through
+//! `!CommandMeasure`. These helpers parse the chord-to-action table so the
+//! plugin can look up what to do for a given chord.
+
+use crate::RainmeterContext;
+use std::fmt;
+
+/// Windows virtual-key codes for the named (non-character) keys, matching
+/// the `VK_*` constants.
+const KEY_NAMES: &[(&str, u16)] = &[
+    ("Backspace", 0x08),
+    ("Tab", 0x09),
+    ("Enter", 0x0D),
+    ("Return", 0x0D),
+    ("Pause", 0x13),
+    ("Esc", 0x1B),
+    ("Escape", 0x1B),
+    ("Space", 0x20),
+    ("PageUp", 0x21),
+    ("PgUp", 0x21),
+    ("PageDown", 0x22),
+    ("PgDn", 0x22),
+    ("End", 0x23),
+    ("Home", 0x24),
+    ("Left", 0x25),
+    ("Up", 0x26),
+    ("Right", 0x27),
+    ("Down", 0x28),
+    ("PrintScreen", 0x2C),
+    ("Insert", 0x2D),
+    ("Ins", 0x2D),
+    ("Delete", 0x2E),
+    ("Del", 0x2E),
+    ("Plus", 0xBB),
+    ("Comma", 0xBC),
+    ("Minus", 0xBD),
+    ("Period", 0xBE),
+];
+
+const VK_F1: u16 = 0x70;
+
+/// A key plus the modifiers held with it, e.g. `Ctrl+Shift+F5`.
+///
+/// `key` is the Windows virtual-key code, so chords compare directly with
+/// codes from `GetAsyncKeyState` or window messages.
+///
+/// ```rust
+/// # use rainmeter::KeyChord;
+/// let chord = KeyChord::parse("ctrl+shift+r").unwrap();
+/// assert!(chord.ctrl && chord.shift && !chord.alt);
+/// assert_eq!(chord.key, u16::from(b'R'));
+/// assert_eq!(chord.to_string(), "Ctrl+Shift+R");
+/// assert_eq!(KeyChord::parse("F5").unwrap().key, 0x74);
+/// assert!(KeyChord::parse("Ctrl+Shift").is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub win: bool,
+    pub key: u16,
+}
+
+/// Parse a key name: a letter or digit, `F1`-`F24` or one of [`KEY_NAMES`].
+fn parse_key(name: &str) -> Option<u16> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return c
+            .is_ascii_alphanumeric()
+            .then(|| c.to_ascii_uppercase() as u16);
+    }
+    let function = name
+        .strip_prefix(['F', 'f'])
+        .and_then(|n| n.parse::<u16>().ok());
+    if let Some(n @ 1..=24) = function {
+        return Some(VK_F1 + n - 1);
+    }
+    KEY_NAMES
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|&(_, code)| code)
+}
+
+impl KeyChord {
+    /// Parse `Modifier+...+Key`. Modifiers are `Ctrl` (or `Control`), `Alt`,
+    /// `Shift` and `Win`, in any order; names ignore case. Exactly one
+    /// non-modifier key is required.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut chord = KeyChord {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            win: false,
+            key: 0,
+        };
+        let mut key = None;
+        for part in text.split('+').map(str::trim) {
+            let modifier = match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => &mut chord.ctrl,
+                "alt" => &mut chord.alt,
+                "shift" => &mut chord.shift,
+                "win" => &mut chord.win,
+                _ => {
+                    if key.replace(parse_key(part)?).is_some() {
+                        return None;
+                    }
+                    continue;
+                }
+            };
+            *modifier = true;
+        }
+        chord.key = key?;
+        Some(chord)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (held, name) in [
+            (self.ctrl, "Ctrl+"),
+            (self.alt, "Alt+"),
+            (self.shift, "Shift+"),
+            (self.win, "Win+"),
+        ] {
+            if held {
+                f.write_str(name)?;
+            }
+        }
+        match self.key {
+            key @ (0x30..=0x39 | 0x41..=0x5A) => write!(f, "{}", key as u8 as char),
+            key if (VK_F1..VK_F1 + 24).contains(&key) => write!(f, "F{}", key - VK_F1 + 1),
+            key => match KEY_NAMES.iter().find(|&&(_, code)| code == key) {
+                Some((name, _)) => f.write_str(name),
+                None => write!(f, "0x{:02X}", key),
+            },
+        }
+    }
+}
+
+impl RainmeterContext {
+    /// Read a `|`-separated table of `chord:action` entries, e.g.
+    /// `Keys=Ctrl+R:refresh | F5:reload | Shift+Esc:[!DeactivateConfig]`.
+    /// The action is everything after the first `:`. Entries with a bad
+    /// chord or an empty action are logged and skipped.
+    ///
+    /// ```rust,ignore
+    /// // MouseActions or a HotKey measure call [!CommandMeasure MyPlugin "Key Ctrl+R"]
+    /// fn execute_bang(&mut self, rm: RainmeterContext, args: &str) {
+    ///     let chord = args.strip_prefix("Key ").and_then(KeyChord::parse);
+    ///     if let Some((_, action)) = self.keys.iter().find(|(c, _)| Some(*c) == chord) {
+    ///         self.run(&rm, action);
+    ///     }
+    /// }
+    /// ```
+    pub fn read_key_bindings(&self, key: &str) -> Vec<(KeyChord, String)> {
+        self.read_list_schema(key, '|', |entry| {
+            let (chord, action) = entry.split_once(':')?;
+            let action = action.trim();
+            if action.is_empty() {
+                return None;
+            }
+            Some((KeyChord::parse(chord)?, action.to_string()))
+        })
+    }
+}
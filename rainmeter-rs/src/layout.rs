@@ -0,0 +1,284 @@
+//! Helpers for positioning meters and reasoning about the screen.
+
+use crate::{OptionEnum, RainmeterContext};
+use windows::Win32::UI::HiDpi::{GetDpiForSystem, GetDpiForWindow};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetSystemMetrics, SM_CXSCREEN, SM_CXVIRTUALSCREEN, SM_CYSCREEN, SM_CYVIRTUALSCREEN,
+    SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+};
+
+/// Build a single command that moves `meter` to `(x, y)` scaled by `scale`,
+/// e.g. `[!SetOption "Meter" X 15][!SetOption "Meter" Y 30]`. Coordinates are
+/// rounded to whole pixels.
+pub fn position_bangs(meter: &str, x: f64, y: f64, scale: f64) -> String {
+    format!(
+        "[!SetOption \"{meter}\" X {}][!SetOption \"{meter}\" Y {}]",
+        (x * scale).round(),
+        (y * scale).round(),
+    )
+}
+
+/// A point on a 3x3 grid over a box, such as the corner or edge a meter is
+/// positioned by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl OptionEnum for Anchor {
+    /// The plain names, plus Rainmeter's `StringAlign` spellings
+    /// (`LeftTop`, `CenterCenter`, ...).
+    const NAMES: &'static [(&'static str, Self)] = &[
+        ("TopLeft", Anchor::TopLeft),
+        ("Top", Anchor::Top),
+        ("TopRight", Anchor::TopRight),
+        ("Left", Anchor::Left),
+        ("Center", Anchor::Center),
+        ("Right", Anchor::Right),
+        ("BottomLeft", Anchor::BottomLeft),
+        ("Bottom", Anchor::Bottom),
+        ("BottomRight", Anchor::BottomRight),
+        ("TopCenter", Anchor::Top),
+        ("CenterLeft", Anchor::Left),
+        ("CenterRight", Anchor::Right),
+        ("BottomCenter", Anchor::Bottom),
+        ("LeftTop", Anchor::TopLeft),
+        ("CenterTop", Anchor::Top),
+        ("RightTop", Anchor::TopRight),
+        ("LeftCenter", Anchor::Left),
+        ("CenterCenter", Anchor::Center),
+        ("RightCenter", Anchor::Right),
+        ("LeftBottom", Anchor::BottomLeft),
+        ("CenterBottom", Anchor::Bottom),
+        ("RightBottom", Anchor::BottomRight),
+    ];
+}
+
+impl Anchor {
+    /// The anchor point's offset from the top-left corner of a `w` by `h`
+    /// box. Subtract it from the position the anchor should land on to get
+    /// the box's top-left corner.
+    ///
+    /// ```rust
+    /// # use rainmeter::Anchor;
+    /// assert_eq!(Anchor::TopLeft.apply(100.0, 40.0), (0.0, 0.0));
+    /// assert_eq!(Anchor::Center.apply(100.0, 40.0), (50.0, 20.0));
+    /// assert_eq!(Anchor::BottomRight.apply(100.0, 40.0), (100.0, 40.0));
+    /// ```
+    pub fn apply(&self, w: f64, h: f64) -> (f64, f64) {
+        let (fx, fy) = match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::Top => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::Bottom => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        };
+        (w * fx, h * fy)
+    }
+}
+
+/// Parse an `x,y` pair of finite numbers.
+fn parse_point(entry: &str) -> Option<(f64, f64)> {
+    let (x, y) = entry.split_once(',')?;
+    let x: f64 = x.trim().parse().ok()?;
+    let y: f64 = y.trim().parse().ok()?;
+    (x.is_finite() && y.is_finite()).then_some((x, y))
+}
+
+impl RainmeterContext {
+    /// Read a `;`-separated list of `x,y` points, e.g.
+    /// `Points=0,0; 10,5; 20,0`, for shape and graph plugins. Malformed
+    /// entries are logged and skipped.
+    pub fn read_points(&self, key: &str) -> Vec<(f64, f64)> {
+        self.read_list_schema(key, ';', parse_point)
+    }
+
+    /// Read an [`Anchor`] such as `Anchor=BottomRight`. Unknown names are
+    /// logged and give `default`.
+    ///
+    /// ```rust,ignore
+    /// // Put the meter's anchor point at (x, y).
+    /// let (dx, dy) = rm.read_anchor("Anchor", Anchor::TopLeft).apply(w, h);
+    /// rm.set_position("MeterPopup", x - dx, y - dy);
+    /// ```
+    pub fn read_anchor(&self, key: &str, default: Anchor) -> Anchor {
+        self.read_enum(key, default)
+    }
+
+    /// DPI scale factor of the monitor the skin window is on (`1.0` at 96 DPI,
+    /// `1.5` at 144 DPI, ...). Falls back to the system DPI if the skin
+    /// window isn't available yet.
+    pub fn skin_scale(&self) -> f64 {
+        let hwnd = self.get_skin_window();
+        let dpi = if hwnd.is_invalid() {
+            0
+        } else {
+            unsafe { GetDpiForWindow(hwnd) }
+        };
+        let dpi = if dpi == 0 {
+            unsafe { GetDpiForSystem() }
+        } else {
+            dpi
+        };
+        dpi as f64 / 96.0
+    }
+
+    /// Move `meter` to `(x, y)` given in 96-DPI units, scaled by
+    /// [`skin_scale`](Self::skin_scale). X and Y are set in one `RmExecute`
+    /// call; the meter only moves on the skin's next redraw, so follow up with
+    /// `[!UpdateMeter ...][!Redraw]` if it has to move immediately.
+    pub fn set_position(&self, meter: &str, x: f64, y: f64) {
+        self.execute(&position_bangs(meter, x, y, self.skin_scale()));
+    }
+
+    /// Size of the primary monitor as `(width, height)`.
+    ///
+    /// `GetSystemMetrics` reports sizes in the DPI-awareness mode of the
+    /// calling thread. Rainmeter is DPI-aware, so inside a plugin these are
+    /// physical pixels; divide by [`skin_scale`](Self::skin_scale) for 96-DPI
+    /// units like those taken by [`set_position`](Self::set_position).
+    pub fn primary_screen_size(&self) -> (i32, i32) {
+        unsafe { (GetSystemMetrics(SM_CXSCREEN), GetSystemMetrics(SM_CYSCREEN)) }
+    }
+
+    /// Bounds of the virtual desktop spanning all monitors, as
+    /// `(left, top, width, height)`. `left`/`top` are negative when a monitor
+    /// sits left of/above the primary one. Same DPI caveats as
+    /// [`primary_screen_size`](Self::primary_screen_size).
+    pub fn virtual_screen_bounds(&self) -> (i32, i32, i32, i32) {
+        unsafe {
+            (
+                GetSystemMetrics(SM_XVIRTUALSCREEN),
+                GetSystemMetrics(SM_YVIRTUALSCREEN),
+                GetSystemMetrics(SM_CXVIRTUALSCREEN),
+                GetSystemMetrics(SM_CYVIRTUALSCREEN),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    const ALL: [(&str, Anchor, (f64, f64)); 9] = [
+        ("TopLeft", Anchor::TopLeft, (0.0, 0.0)),
+        ("Top", Anchor::Top, (50.0, 0.0)),
+        ("TopRight", Anchor::TopRight, (100.0, 0.0)),
+        ("Left", Anchor::Left, (0.0, 20.0)),
+        ("Center", Anchor::Center, (50.0, 20.0)),
+        ("Right", Anchor::Right, (100.0, 20.0)),
+        ("BottomLeft", Anchor::BottomLeft, (0.0, 40.0)),
+        ("Bottom", Anchor::Bottom, (50.0, 40.0)),
+        ("BottomRight", Anchor::BottomRight, (100.0, 40.0)),
+    ];
+
+    #[test]
+    fn every_anchor_has_a_name_and_offset() {
+        for (name, anchor, offset) in ALL {
+            assert_eq!(anchor.name(), Some(name));
+            assert_eq!(Anchor::from_name(name), Some(anchor));
+            assert_eq!(anchor.apply(100.0, 40.0), offset, "{}", name);
+        }
+    }
+
+    #[test]
+    fn reads_anchors_ignoring_case() {
+        let rm = MockContext::new();
+        for (value, expected) in [
+            ("bottomright", Anchor::BottomRight),
+            ("TOPLEFT", Anchor::TopLeft),
+            ("centerCenter", Anchor::Center),
+            ("RightTop", Anchor::TopRight),
+            (" Bottom ", Anchor::Bottom),
+        ] {
+            rm.set("Anchor", value);
+            assert_eq!(rm.read_anchor("Anchor", Anchor::Left), expected);
+        }
+        assert!(rm.warnings().is_empty());
+    }
+
+    #[test]
+    fn invalid_anchors_give_the_default() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_anchor("Anchor", Anchor::Center), Anchor::Center);
+        for value in ["Middle", "Top-Left", "5"] {
+            rm.set("Anchor", value);
+            assert_eq!(rm.read_anchor("Anchor", Anchor::Center), Anchor::Center);
+        }
+        assert_eq!(
+            rm.warnings(),
+            [
+                "Anchor: unknown value 'Middle'",
+                "Anchor: unknown value 'Top-Left'",
+                "Anchor: unknown value '5'",
+            ]
+        );
+    }
+
+    #[test]
+    fn position_bangs_scale_and_round() {
+        assert_eq!(
+            position_bangs("MeterPopup", 10.0, 21.0, 1.5),
+            "[!SetOption \"MeterPopup\" X 15][!SetOption \"MeterPopup\" Y 32]"
+        );
+        assert_eq!(
+            position_bangs("M", -2.4, 0.0, 1.0),
+            "[!SetOption \"M\" X -2][!SetOption \"M\" Y 0]"
+        );
+    }
+
+    #[test]
+    fn set_position_sends_one_batched_bang() {
+        let rm = MockContext::new();
+        rm.set_skin_window(0x10);
+        rm.set_position("MeterPopup", 10.0, 21.0);
+        assert_eq!(
+            rm.bangs(),
+            [position_bangs("MeterPopup", 10.0, 21.0, rm.skin_scale())]
+        );
+        assert!(rm.skin_scale() > 0.0);
+    }
+
+    #[test]
+    fn primary_screen_has_a_size() {
+        let (width, height) = MockContext::new().primary_screen_size();
+        assert!(width > 0 && height > 0, "{}x{}", width, height);
+    }
+
+    #[test]
+    fn reads_points() {
+        let rm = MockContext::new();
+        rm.set("Points", " 0,0;10 , 5.5 ;  ; -20,1e1 ");
+        assert_eq!(
+            rm.read_points("Points"),
+            [(0.0, 0.0), (10.0, 5.5), (-20.0, 10.0)]
+        );
+        assert!(rm.warnings().is_empty());
+
+        rm.set("Points", "0,0; 10; 1,2,3; a,b; 5,inf; 20,0");
+        assert_eq!(rm.read_points("Points"), [(0.0, 0.0), (20.0, 0.0)]);
+        assert_eq!(
+            rm.warnings(),
+            [
+                "Points: skipping invalid entry '10'".to_string(),
+                "Points: skipping invalid entry '1,2,3'".to_string(),
+                "Points: skipping invalid entry 'a,b'".to_string(),
+                "Points: skipping invalid entry '5,inf'".to_string(),
+            ]
+        );
+    }
+}
@@ -54,19 +54,155 @@
 //! declare_plugin!(crate::MyPlugin);
 //! ```
 
+#[cfg(test)]
+use mock::ffi::*;
+use once_cell::sync::OnceCell;
+#[cfg(not(test))]
 use rainmeter_sys::*;
 use std::ffi::{OsStr, c_void};
 use std::os::windows::ffi::OsStrExt;
 use windows::Win32::Foundation::HWND;
 use windows::core::{BOOL, PCWSTR};
+
+mod actions;
+mod clipboard;
+mod color;
+mod command_queue;
+mod command_template;
+mod condition;
+mod curve;
+mod cycle;
+mod data_file;
+mod debounce;
+mod debug_overlay;
+mod dir_watcher;
+mod dynamic_vars;
+mod easing;
+mod encoding;
+mod error;
+mod failover;
+mod features;
+mod font;
+mod format;
+mod histogram;
+mod history;
+#[cfg(feature = "http")]
+mod http;
+mod ini;
+#[cfg(feature = "json")]
+mod json;
+mod keys;
+mod layout;
+mod log_buffer;
+mod measures;
+#[cfg(test)]
+mod mock;
+mod notify;
+mod options;
+mod paths;
+mod perf_counter;
+mod process_runner;
+mod processes;
+pub mod ranges;
+mod read_options;
+mod registry;
+mod retry;
+#[cfg(feature = "lua")]
+mod script;
+mod sections;
+mod shared_cache;
+mod skin;
+mod smoothing;
+#[cfg(feature = "json")]
+pub mod state;
+mod style;
+#[cfg(feature = "regex")]
+mod substitute;
+mod timeline;
+mod timezone;
+mod timing;
+mod trace;
+mod transform;
+mod units;
+mod weekday;
+#[cfg(feature = "wmi")]
+mod wmi;
+
+pub use actions::ActionMap;
+pub use color::{BlendMode, Heatmap, Rgba, parse_color, system_uses_light_theme};
+pub use command_queue::{Command, CommandQueue};
+pub use command_template::CommandTemplate;
+pub use condition::{CompareOp, Condition};
+pub use curve::Curve;
+pub use cycle::{CycleOrder, Cycler};
+pub use data_file::DataFormat;
+pub use debounce::Debouncer;
+pub use dir_watcher::{DirStatus, DirWatcher};
+pub use dynamic_vars::DynamicVarsGuard;
+pub use easing::Easing;
+pub use encoding::Encoding;
+pub use error::RmError;
+pub use failover::Failover;
+pub use features::FeatureSet;
+pub use font::{FontSpec, FontStyle, font_exists};
+pub use format::{
+    DurationStyle, PluralTemplate, duration_string, format_duration, format_value,
+    truncate_ellipsis,
+};
+pub use histogram::Histogram;
+pub use history::History;
+#[cfg(feature = "http")]
+pub use http::HttpCache;
+#[cfg(feature = "json")]
+pub use json::JsonExtract;
+pub use keys::KeyChord;
+pub use layout::{Anchor, position_bangs};
+pub use log_buffer::{MAX_RECENT_LOGS, keep_recent_logs, recent_logs};
+pub use measures::Aggregate;
+pub use options::{OptionEnum, percent_decode};
+pub use perf_counter::PerfCounter;
+pub use process_runner::{ProcessRunner, ProcessStatus};
+pub use processes::{ProcessInfo, current_memory_usage, list_processes};
+#[doc(hidden)]
+pub use read_options::OptionDefault;
+pub use read_options::ReadOption;
+pub use retry::RetryPolicy;
+#[cfg(feature = "lua")]
+pub use script::CompiledScript;
+pub use sections::SectionOptions;
+pub use shared_cache::SharedCache;
+pub use smoothing::{Smoother, SmoothingMethod};
+pub use style::{IconMap, StyleRule, StyleRules};
+#[cfg(feature = "regex")]
+pub use substitute::RegexSubstitutions;
+pub use timeline::Timeline;
+pub use timezone::{TimeZone, now_in};
+pub use timing::{TimeBudget, TimerSet, WallClockTimer, parse_duration};
+pub use transform::{Pipeline, Stage, apply_transform};
+pub use units::format_si;
+pub use weekday::Weekday;
+#[cfg(feature = "wmi")]
+pub use wmi::wmi_query;
+
 // -----------------------------------------------------------------------
 // Helpers: wide‑string conversion
 // -----------------------------------------------------------------------
 
-fn to_pcwstr(s: &str) -> PCWSTR {
+/// A NUL-terminated UTF-16 copy of a `&str`. The pointer from `as_ptr` is
+/// valid for as long as this value is alive, so keep it bound to a local
+/// across the FFI call.
+struct WideString(Vec<u16>);
+
+impl WideString {
+    fn as_ptr(&self) -> *const u16 {
+        self.0.as_ptr()
+    }
+}
+
+fn to_wide(s: &str) -> WideString {
     let mut wide: Vec<u16> = OsStr::new(s).encode_wide().collect();
     wide.push(0);
-    PCWSTR(wide.as_ptr())
+    WideString(wide)
 }
 
 unsafe fn from_pcwstr(ptr: PCWSTR) -> String {
@@ -116,37 +252,151 @@ impl RainmeterContext {
         Self { raw }
     }
 
+    /// Log an FFI call at debug level if `__RmRsTrace=1` is set on this
+    /// measure. `call` is only evaluated when tracing is on.
+    #[inline]
+    fn trace(&self, call: impl FnOnce() -> String) {
+        if trace::is_traced(self.raw) {
+            let m = to_wide(&format!("[trace] {}", call()));
+            unsafe { RmLog(self.raw, RmLogLevel::LogDebug as i32, m.as_ptr()) };
+        }
+    }
+
+    /// Re-read the hidden `__RmRsTrace` option for this measure.
+    /// `declare_plugin!` calls this from `Initialize` and `Reload`.
+    #[doc(hidden)]
+    pub fn refresh_trace(&self) {
+        let k = to_wide(trace::TRACE_OPTION);
+        let enabled = unsafe { RmReadFormula(self.raw, k.as_ptr(), 0.0) } != 0.0;
+        trace::set_traced(self.raw, enabled);
+    }
+
+    /// Stop tracing this measure. `declare_plugin!` calls this from `Finalize`.
+    #[doc(hidden)]
+    pub fn clear_trace(&self) {
+        trace::set_traced(self.raw, false);
+    }
+
+    /// Re-read the `DebugOverlay` option for this measure (see
+    /// [`RainmeterPlugin::debug_overlay_string`]). `declare_plugin!` calls
+    /// this from `Initialize` and `Reload`.
+    #[doc(hidden)]
+    pub fn refresh_debug_overlay(&self) {
+        let k = to_wide(debug_overlay::OVERLAY_OPTION);
+        let enabled = unsafe { RmReadFormula(self.raw, k.as_ptr(), 0.0) } != 0.0;
+        debug_overlay::set_enabled(self.raw, enabled);
+    }
+
+    /// Turn the overlay off for this measure. `declare_plugin!` calls this
+    /// from `Finalize`.
+    #[doc(hidden)]
+    pub fn clear_debug_overlay(&self) {
+        debug_overlay::set_enabled(self.raw, false);
+    }
+
+    /// Whether this measure has `DebugOverlay=1`.
+    #[doc(hidden)]
+    pub fn debug_overlay_enabled(&self) -> bool {
+        debug_overlay::is_enabled(self.raw)
+    }
+
+    /// Record the value `update` returned, for the overlay.
+    #[doc(hidden)]
+    pub fn note_update_value(&self, value: f64) {
+        debug_overlay::note_value(self.raw, value);
+    }
+
+    /// The `DebugOverlay=1` dump for this measure, ending with `plugin`
+    /// (the plugin's [`debug_overlay_string`](RainmeterPlugin::debug_overlay_string)),
+    /// or `None` if the option isn't set:
+    ///
+    /// ```text
+    /// MeasureWeather (DebugOverlay)
+    /// Value: 21.5
+    /// Last error: Url: invalid URL ''
+    /// Options:
+    ///   Url=
+    ///   RefreshInterval=10m
+    /// Plugin: weather::Weather
+    /// ```
+    pub fn debug_overlay_report(&self, plugin: &str) -> Option<String> {
+        debug_overlay::render(self.raw, &self.get_measure_name(), plugin)
+    }
+
     // --- Section readers ---
     pub fn read_string(&self, key: &str, default: &str) -> String {
-        let k = to_pcwstr(key);
-        let d = to_pcwstr(default);
+        dynamic_vars::note_option_read();
+        self.trace(|| format!("RmReadString({:?}, {:?}, 1)", key, default));
+        let k = to_wide(key);
+        let d = to_wide(default);
         // FFI expects *const u16, and replaceMeasures as a plain i32
         let raw_ptr = unsafe {
             RmReadString(
-                self.raw, k.0, d.0, 1, // TRUE
+                self.raw,
+                k.as_ptr(),
+                d.as_ptr(),
+                1, // TRUE
             )
         };
         // Wrap it back so our from_pcwstr() still works
-        unsafe { from_pcwstr(PCWSTR(raw_ptr)) }
+        let value = unsafe { from_pcwstr(PCWSTR(raw_ptr)) };
+        debug_overlay::note_option(self.raw, key, &value);
+        value
+    }
+
+    /// Like `read_string`, but leaves section variables (`[Measure]`)
+    /// unexpanded. `#Variables#` are still replaced.
+    pub fn read_string_raw(&self, key: &str, default: &str) -> String {
+        dynamic_vars::note_option_read();
+        self.trace(|| format!("RmReadString({:?}, {:?}, 0)", key, default));
+        let k = to_wide(key);
+        let d = to_wide(default);
+        let raw_ptr = unsafe { RmReadString(self.raw, k.as_ptr(), d.as_ptr(), 0) };
+        let value = unsafe { from_pcwstr(PCWSTR(raw_ptr)) };
+        debug_overlay::note_option(self.raw, key, &value);
+        value
     }
 
     pub fn read_string_section(&self, section: &str, key: &str, default: &str) -> String {
-        let s = to_pcwstr(section);
-        let k = to_pcwstr(key);
-        let d = to_pcwstr(default);
-        let raw_ptr = unsafe { RmReadStringFromSection(self.raw, s.0, k.0, d.0, 1) };
-        unsafe { from_pcwstr(PCWSTR(raw_ptr)) }
+        dynamic_vars::note_option_read();
+        self.trace(|| {
+            format!(
+                "RmReadStringFromSection({:?}, {:?}, {:?}, 1)",
+                section, key, default
+            )
+        });
+        let s = to_wide(section);
+        let k = to_wide(key);
+        let d = to_wide(default);
+        let raw_ptr =
+            unsafe { RmReadStringFromSection(self.raw, s.as_ptr(), k.as_ptr(), d.as_ptr(), 1) };
+        let value = unsafe { from_pcwstr(PCWSTR(raw_ptr)) };
+        debug_overlay::note_section_option(self.raw, section, key, &value);
+        value
     }
 
     pub fn read_formula(&self, key: &str, default: f64) -> f64 {
-        let k = to_pcwstr(key);
-        unsafe { RmReadFormula(self.raw, k.0, default) }
+        dynamic_vars::note_option_read();
+        self.trace(|| format!("RmReadFormula({:?}, {})", key, default));
+        let k = to_wide(key);
+        let value = unsafe { RmReadFormula(self.raw, k.as_ptr(), default) };
+        debug_overlay::note_option(self.raw, key, value);
+        value
     }
 
     pub fn read_formula_section(&self, section: &str, key: &str, default: f64) -> f64 {
-        let s = to_pcwstr(section);
-        let k = to_pcwstr(key);
-        unsafe { RmReadFormulaFromSection(self.raw, s.0, k.0, default) }
+        dynamic_vars::note_option_read();
+        self.trace(|| {
+            format!(
+                "RmReadFormulaFromSection({:?}, {:?}, {})",
+                section, key, default
+            )
+        });
+        let s = to_wide(section);
+        let k = to_wide(key);
+        let value = unsafe { RmReadFormulaFromSection(self.raw, s.as_ptr(), k.as_ptr(), default) };
+        debug_overlay::note_section_option(self.raw, section, key, value);
+        value
     }
 
     pub fn read_int(&self, key: &str, default: i32) -> i32 {
@@ -157,6 +407,11 @@ impl RainmeterContext {
         self.read_formula_section(section, key, default as f64) as i32
     }
 
+    /// Read a Rainmeter-style boolean: any non-zero number (or formula) is `true`.
+    pub fn read_bool(&self, key: &str, default: bool) -> bool {
+        self.read_formula(key, if default { 1.0 } else { 0.0 }) != 0.0
+    }
+
     pub fn read_double(&self, key: &str, default: f64) -> f64 {
         self.read_formula(key, default)
     }
@@ -166,14 +421,16 @@ impl RainmeterContext {
     }
 
     pub fn replace_variables(&self, input: &str) -> String {
-        let i = to_pcwstr(input);
-        let raw_ptr = unsafe { RmReplaceVariables(self.raw, i.0) };
+        self.trace(|| format!("RmReplaceVariables({:?})", input));
+        let i = to_wide(input);
+        let raw_ptr = unsafe { RmReplaceVariables(self.raw, i.as_ptr()) };
         unsafe { from_pcwstr(PCWSTR(raw_ptr)) }
     }
     /// No, Minthara... I am busy right now.
     pub fn path_to_absolute(&self, relative: &str) -> String {
-        let r = to_pcwstr(relative);
-        let raw_ptr = unsafe { RmPathToAbsolute(self.raw, r.0) };
+        self.trace(|| format!("RmPathToAbsolute({:?})", relative));
+        let r = to_wide(relative);
+        let raw_ptr = unsafe { RmPathToAbsolute(self.raw, r.as_ptr()) };
         unsafe { from_pcwstr(PCWSTR(raw_ptr)) }
     }
 
@@ -183,13 +440,17 @@ impl RainmeterContext {
     }
 
     pub fn execute(&self, command: &str) {
-        let c = to_pcwstr(command);
-        unsafe { RmExecute(self.get_skin(), c.0) };
+        let skin = self.get_skin();
+        self.trace(|| format!("RmExecute({:?})", command));
+        let c = to_wide(command);
+        unsafe { RmExecute(skin, c.as_ptr()) };
     }
 
     /// Raw RmGet with integer code
     pub fn get_raw(&self, what: RmGetType) -> *mut c_void {
-        unsafe { RmGet(self.raw, what as i32) }
+        let what = what as i32;
+        self.trace(|| format!("RmGet({})", what));
+        unsafe { RmGet(self.raw, what) }
     }
 
     /// Retrieve raw PCWSTR for measure name
@@ -215,6 +476,7 @@ impl RainmeterContext {
 
     /// Raw PCWSTR for settings file path
     pub fn get_settings_file_raw(&self) -> PCWSTR {
+        self.trace(|| format!("RmGet(NULL, {})", RmGetType::SettingsFile as i32));
         PCWSTR(unsafe { RmGet(std::ptr::null_mut(), RmGetType::SettingsFile as i32) } as _)
     }
 
@@ -224,6 +486,24 @@ impl RainmeterContext {
         unsafe { from_pcwstr(ptr) }
     }
 
+    /// Settings file path, resolved once and cached for the rest of the session.
+    ///
+    /// The path is global to the Rainmeter process (`RmGet` is called without
+    /// a measure pointer) and can't change while it runs, so every measure
+    /// shares the same cached value. An empty result isn't cached, so a
+    /// failed early lookup is retried on the next call.
+    pub fn get_settings_file_cached(&self) -> &'static str {
+        static SETTINGS_FILE: OnceCell<String> = OnceCell::new();
+        if let Some(path) = SETTINGS_FILE.get() {
+            return path;
+        }
+        let path = self.get_settings_file();
+        if path.is_empty() {
+            return "";
+        }
+        SETTINGS_FILE.get_or_init(|| path)
+    }
+
     /// Raw PCWSTR for skin name
     pub fn get_skin_name_raw(&self) -> PCWSTR {
         PCWSTR(self.get_raw(RmGetType::SkinName) as _)
@@ -246,8 +526,28 @@ impl RainmeterContext {
     }
 
     pub fn log(&self, level: RmLogLevel, message: &str) {
-        let m = to_pcwstr(message);
-        unsafe { RmLog(self.raw, level as i32, m.0) };
+        if matches!(level, RmLogLevel::LogError | RmLogLevel::LogWarning) {
+            debug_overlay::note_error(self.raw, message);
+        }
+        if log_buffer::is_enabled() {
+            log_buffer::push(&level, &self.get_measure_name(), message);
+        }
+        let level = level as i32;
+        self.trace(|| format!("RmLog({}, {:?})", level, message));
+        let m = to_wide(message);
+        unsafe { RmLog(self.raw, level, m.as_ptr()) };
+    }
+
+    /// Log with a `[tag]` prefix, e.g. to tell a plugin's subsystems apart:
+    /// `rm.log_tagged(RmLogLevel::LogWarning, "net", "timeout")` logs `[net] timeout`.
+    pub fn log_tagged(&self, level: RmLogLevel, tag: &str, message: &str) {
+        self.log(level, &format!("[{}] {}", tag, message));
+    }
+
+    /// Log `fields` as a single compact JSON object, e.g.
+    /// `{"event":"fetch","status":"timeout"}`, for log aggregators.
+    pub fn log_json(&self, level: RmLogLevel, fields: &[(&str, &str)]) {
+        self.log(level, &format::json_object(fields));
     }
 }
 
@@ -261,7 +561,25 @@ impl Clone for RainmeterContext {
 
 /// Trait to implement for your plugin. Defines the six Rainmeter entry points.
 pub trait RainmeterPlugin: Default + 'static {
+    /// Called once when the measure is created, before the first
+    /// [`reload`](Self::reload).
+    ///
+    /// `rm` is fully usable here: every `read_*` method, `log`, `execute`
+    /// and the `RmGet` queries (measure name, skin, settings file) behave
+    /// exactly as they do in `reload`, and `declare_plugin!` sets up tracing
+    /// and `DebugOverlay` before calling this. So one-off settings can be
+    /// read here and the rest left to `reload`.
+    ///
+    /// The one difference is timing, not the API: no measure has updated
+    /// yet, so section variables such as `[MeasureCPU]` in an option
+    /// resolve to their initial value (usually `0` or an empty string).
+    /// Options that depend on other measures belong in `reload`, which
+    /// Rainmeter calls right after this and, with `DynamicVariables=1`,
+    /// again before every update.
     fn initialize(&mut self, rm: RainmeterContext);
+    /// Called after [`initialize`](Self::initialize), on every skin refresh
+    /// and, with `DynamicVariables=1`, before every update. `max_value`
+    /// holds the measure's `MaxValue=` and may be changed.
     fn reload(&mut self, rm: RainmeterContext, max_value: &mut f64);
     fn update(&mut self, rm: RainmeterContext) -> f64;
     fn get_string(&mut self, _rm: RainmeterContext) -> Option<String> {
@@ -269,6 +587,14 @@ pub trait RainmeterPlugin: Default + 'static {
     }
     fn execute_bang(&mut self, _rm: RainmeterContext, _args: &str) {}
     fn finalize(&mut self, rm: RainmeterContext);
+
+    /// Plugin-specific lines for the measure string while the measure has
+    /// `DebugOverlay=1`; `declare_plugin!` appends them to the generic dump
+    /// from [`RainmeterContext::debug_overlay_report`] in place of
+    /// `get_string`. The default just names the plugin type.
+    fn debug_overlay_string(&self) -> String {
+        format!("Plugin: {}", std::any::type_name::<Self>())
+    }
 }
 
 /// Glue macro to expose your Rust `RainmeterPlugin` implementation
@@ -319,6 +645,8 @@ macro_rules! declare_plugin {
                     plugin: <$plugin>::default(),
                     rm_raw: rm,
                 });
+                RainmeterContext::new(rm).refresh_trace();
+                RainmeterContext::new(rm).refresh_debug_overlay();
                 let result = panic::catch_unwind(AssertUnwindSafe(|| {
                     entry.plugin.initialize(RainmeterContext::new(rm));
                 }));
@@ -337,6 +665,8 @@ macro_rules! declare_plugin {
             ) {
                 let mut entry = unsafe { &mut *(data as *mut PluginEntry) };
                 entry.rm_raw = rm;
+                RainmeterContext::new(rm).refresh_trace();
+                RainmeterContext::new(rm).refresh_debug_overlay();
                 let mut default = unsafe { *max_value };
                 let result = panic::catch_unwind(AssertUnwindSafe(|| {
                     entry.plugin.reload(RainmeterContext::new(rm), &mut default);
@@ -357,6 +687,7 @@ macro_rules! declare_plugin {
                 if let Err(err) = result {
                     log_panic(entry.rm_raw, "Update", err);
                 }
+                RainmeterContext::new(entry.rm_raw).note_update_value(ret);
                 ret
             }
 
@@ -365,7 +696,13 @@ macro_rules! declare_plugin {
                 let mut entry = unsafe { &mut *(data as *mut PluginEntry) };
                 let mut out_ptr = std::ptr::null();
                 let result = panic::catch_unwind(AssertUnwindSafe(|| {
-                    if let Some(s) = entry.plugin.get_string(RainmeterContext::new(entry.rm_raw)) {
+                    let rm = RainmeterContext::new(entry.rm_raw);
+                    let string = if rm.debug_overlay_enabled() {
+                        rm.debug_overlay_report(&entry.plugin.debug_overlay_string())
+                    } else {
+                        entry.plugin.get_string(rm)
+                    };
+                    if let Some(s) = string {
                         let mut wide: Vec<u16> =
                             OsStr::new(&s).encode_wide().chain(Some(0)).collect();
                         out_ptr = wide.as_mut_ptr();
@@ -411,7 +748,129 @@ macro_rules! declare_plugin {
                 if let Err(err) = result {
                     log_panic(entry.rm_raw, "Finalize", err);
                 }
+                RainmeterContext::new(entry.rm_raw).clear_trace();
+                RainmeterContext::new(entry.rm_raw).clear_debug_overlay();
             }
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    /// Records everything it reads in `initialize` and in each `reload`.
+    #[derive(Default)]
+    struct Recorder {
+        reads: Vec<Vec<String>>,
+    }
+
+    impl Recorder {
+        fn record(&mut self, rm: &RainmeterContext) {
+            self.reads.push(vec![
+                rm.read_string("Text", "none"),
+                rm.read_string_raw("Text", "none"),
+                rm.read_string_section("Variables", "Color", "none"),
+                rm.read_int("Count", -1).to_string(),
+                rm.read_double("Scale", -1.0).to_string(),
+                rm.read_bool("Enabled", false).to_string(),
+                rm.read_path("File", ""),
+                rm.replace_variables("#Color#"),
+                rm.get_measure_name(),
+                rm.get_skin_name(),
+                rm.get_settings_file(),
+            ]);
+        }
+    }
+
+    impl RainmeterPlugin for Recorder {
+        fn initialize(&mut self, rm: RainmeterContext) {
+            self.record(&rm);
+        }
+        fn reload(&mut self, rm: RainmeterContext, _max_value: &mut f64) {
+            self.record(&rm);
+        }
+        fn update(&mut self, _rm: RainmeterContext) -> f64 {
+            0.0
+        }
+        fn finalize(&mut self, _rm: RainmeterContext) {}
+    }
+
+    #[test]
+    fn initialize_reads_match_reload_reads() {
+        let rm = MockContext::new();
+        rm.set("Text", "#Color# [MeasureCPU]")
+            .set("Count", "3")
+            .set("Scale", "1.5")
+            .set("Enabled", "1")
+            .set("File", "data.txt")
+            .set_in("Variables", "Color", "255,0,0")
+            .set_variable("#Color#", "255,0,0")
+            .set_variable("[MeasureCPU]", "0");
+
+        // The order `declare_plugin!` calls things in.
+        let mut plugin = Recorder::default();
+        rm.refresh_trace();
+        rm.refresh_debug_overlay();
+        plugin.initialize(rm.context());
+        rm.refresh_trace();
+        rm.refresh_debug_overlay();
+        plugin.reload(rm.context(), &mut 0.0);
+
+        assert_eq!(plugin.reads[0], plugin.reads[1]);
+        assert_eq!(plugin.reads[0][0], "255,0,0 0");
+        assert_eq!(plugin.reads[0][1], "255,0,0 [MeasureCPU]");
+        assert!(rm.warnings().is_empty());
+    }
+
+    #[test]
+    fn tagged_logs_keep_the_level() {
+        let rm = MockContext::new();
+        rm.log_tagged(RmLogLevel::LogWarning, "network", "timed out");
+        rm.log_tagged(RmLogLevel::LogDebug, "cache", "hit");
+        assert_eq!(
+            rm.logs(),
+            [
+                (
+                    RmLogLevel::LogWarning as i32,
+                    "[network] timed out".to_string()
+                ),
+                (RmLogLevel::LogDebug as i32, "[cache] hit".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn settings_file_is_cached() {
+        let rm = MockContext::new();
+        let cached = rm.get_settings_file_cached();
+        assert!(cached.ends_with("Rainmeter.ini"), "{}", cached);
+        assert_eq!(cached, rm.get_settings_file());
+        assert!(std::ptr::eq(cached, rm.get_settings_file_cached()));
+        assert!(std::ptr::eq(
+            cached,
+            MockContext::new().get_settings_file_cached()
+        ));
+    }
+
+    #[test]
+    fn json_logs_are_escaped() {
+        let rm = MockContext::new();
+        rm.log_json(
+            RmLogLevel::LogNotice,
+            &[
+                ("event", "fetch"),
+                ("path", "C:\\Skins\\\"new\"\n"),
+                ("raw", "\t\u{1}"),
+            ],
+        );
+        assert_eq!(
+            rm.logs(),
+            [(
+                RmLogLevel::LogNotice as i32,
+                r#"{"event":"fetch","path":"C:\\Skins\\\"new\"\n","raw":"\t\u0001"}"#.to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! An opt-in, process-wide record of the last messages logged through
+//! [`RainmeterContext::log`](crate::RainmeterContext::log), so a skin can
+//! show recent plugin activity without the user opening Rainmeter's log
+//! window.
+//!
+//! Until a plugin calls [`keep_recent_logs`], logging costs a single relaxed
+//! atomic load: the measure name isn't fetched and no lock is taken.
+
+use crate::RmLogLevel;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The most messages [`keep_recent_logs`] will keep.
+pub const MAX_RECENT_LOGS: usize = 50;
+
+/// How many messages to keep; `0` (the default) disables the buffer.
+static CAPACITY: AtomicUsize = AtomicUsize::new(0);
+static RECENT: Mutex<RecentLogs> = Mutex::new(RecentLogs::new());
+
+/// A bounded queue of formatted messages, oldest first.
+struct RecentLogs {
+    entries: VecDeque<String>,
+}
+
+impl RecentLogs {
+    const fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Add `entry`, dropping the oldest entries beyond `capacity`.
+    fn push(&mut self, capacity: usize, entry: String) {
+        self.entries.push_back(entry);
+        while self.entries.len() > capacity {
+            self.entries.pop_front();
+        }
+    }
+}
+
+/// Start (or stop) keeping the last `capacity` messages for
+/// [`recent_logs`], up to [`MAX_RECENT_LOGS`]. `0` turns the buffer off
+/// and empties it. Call it once, e.g. from `initialize`; the setting is
+/// shared by every measure using the plugin DLL.
+pub fn keep_recent_logs(capacity: usize) {
+    let capacity = capacity.min(MAX_RECENT_LOGS);
+    let mut recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+    CAPACITY.store(capacity, Ordering::Relaxed);
+    while recent.entries.len() > capacity {
+        recent.entries.pop_front();
+    }
+}
+
+/// Whether [`push`] would record anything, so callers can skip building
+/// the entry.
+pub(crate) fn is_enabled() -> bool {
+    CAPACITY.load(Ordering::Relaxed) != 0
+}
+
+/// Record a message, dropping the oldest once the buffer is full.
+pub(crate) fn push(level: &RmLogLevel, measure: &str, message: &str) {
+    let level = match level {
+        RmLogLevel::LogError => "Error",
+        RmLogLevel::LogWarning => "Warning",
+        RmLogLevel::LogNotice => "Notice",
+        RmLogLevel::LogDebug => "Debug",
+    };
+    let mut recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+    let capacity = CAPACITY.load(Ordering::Relaxed);
+    if capacity != 0 {
+        recent.push(capacity, format!("[{}] {}: {}", level, measure, message));
+    }
+}
+
+/// The messages logged by any measure of this plugin since
+/// [`keep_recent_logs`] was called, oldest first, each as
+/// `[Level] Measure: message`. Empty while the buffer is off.
+///
+/// The buffer lasts until Rainmeter unloads the plugin DLL. A measure can
+/// show it as its string value:
+///
+/// ```rust,ignore
+/// fn initialize(&mut self, rm: RainmeterContext) {
+///     if rm.read_bool("ShowLog", false) {
+///         rainmeter::keep_recent_logs(20);
+///     }
+/// }
+///
+/// fn get_string(&mut self, _rm: RainmeterContext) -> Option<String> {
+///     self.show_log.then(|| rainmeter::recent_logs().join("\n"))
+/// }
+/// ```
+pub fn recent_logs() -> Vec<String> {
+    let recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+    recent.entries.iter().cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn evicts_oldest_entries_first() {
+        let mut recent = RecentLogs::new();
+        for i in 0..5 {
+            recent.push(3, i.to_string());
+        }
+        assert_eq!(recent.entries, ["2", "3", "4"]);
+        recent.push(1, "5".to_string());
+        assert_eq!(recent.entries, ["5"]);
+    }
+
+    // The buffer is process-wide, so enabling it is kept to this one test;
+    // other tests logging in parallel may still add entries while it's on.
+    #[test]
+    fn logging_fills_the_buffer_only_when_enabled() {
+        let rm = MockContext::new();
+        rm.set_measure_name("MeasureLogBuffer");
+        rm.log(RmLogLevel::LogNotice, "before");
+        assert!(recent_logs().is_empty());
+
+        keep_recent_logs(MAX_RECENT_LOGS + 10);
+        assert_eq!(CAPACITY.load(Ordering::Relaxed), MAX_RECENT_LOGS);
+        for i in 0..MAX_RECENT_LOGS + 5 {
+            rm.log(RmLogLevel::LogWarning, &i.to_string());
+        }
+        let ours: Vec<String> = recent_logs()
+            .into_iter()
+            .filter(|entry| entry.contains("MeasureLogBuffer"))
+            .collect();
+        assert!(ours.len() <= MAX_RECENT_LOGS);
+        assert_eq!(
+            ours.last().unwrap(),
+            &format!("[Warning] MeasureLogBuffer: {}", MAX_RECENT_LOGS + 4)
+        );
+        assert!(!ours.iter().any(|entry| entry.ends_with(": 4")));
+
+        keep_recent_logs(0);
+        assert!(recent_logs().is_empty());
+        rm.log(RmLogLevel::LogNotice, "after");
+        assert!(recent_logs().is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+//! Reading other measures' values through section variables.
+//!
+//! `replace_variables` expands section variables as well as `#Variables#`.
+//! For measures Rainmeter supports, among others:
+//!
+//! - `[Measure]` / `[Measure:]` – string / number value
+//! - `[Measure:MaxValue]`, `[Measure:MinValue]` – the measure's range
+//! - `[Measure:%]` – value as a percentage of its range
+//! - `[Measure:/1000]`, `[Measure:2]` – scaled / fixed-decimal value
+//! - `[Measure:Timestamp]` – for Time measures
+//!
+//! A section variable that can't be resolved (unknown measure, or a measure
+//! that hasn't updated yet) is returned unchanged, which these helpers report
+//! as `None`. The measure using them usually needs `DynamicVariables=1` to
+//! see up-to-date values.
+
+use crate::options::lookup_mapped;
+use crate::{RainmeterContext, RmLogLevel};
+
+/// How [`RainmeterContext::aggregate`] combines several measures' values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+const AGGREGATE_NAMES: &[(&str, Aggregate)] = &[
+    ("sum", Aggregate::Sum),
+    ("avg", Aggregate::Avg),
+    ("average", Aggregate::Avg),
+    ("min", Aggregate::Min),
+    ("max", Aggregate::Max),
+];
+
+impl Aggregate {
+    /// Parse `sum`, `avg` (or `average`), `min` or `max`, ignoring case.
+    pub fn parse(name: &str) -> Option<Self> {
+        lookup_mapped(name, AGGREGATE_NAMES)
+    }
+
+    /// Combine `values`, or `None` if there are none.
+    pub fn apply(self, values: &[f64]) -> Option<f64> {
+        if values.is_empty() {
+            return None;
+        }
+        let iter = values.iter().copied();
+        Some(match self {
+            Aggregate::Sum => iter.sum(),
+            Aggregate::Avg => iter.sum::<f64>() / values.len() as f64,
+            Aggregate::Min => iter.fold(f64::INFINITY, f64::min),
+            Aggregate::Max => iter.fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+impl RainmeterContext {
+    /// Resolve `[measure:parameter]` and parse the result as a number.
+    pub(crate) fn read_measure_number(&self, measure: &str, parameter: &str) -> Option<f64> {
+        self.resolve_variable(&format!("[{}:{}]", measure.trim(), parameter))?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// Current numeric value of `measure` (`[measure:]`).
+    pub fn read_measure_value(&self, measure: &str) -> Option<f64> {
+        self.read_measure_number(measure, "")
+    }
+
+    /// `MaxValue` of `measure` (`[measure:MaxValue]`), e.g. to adopt another
+    /// measure's range.
+    pub fn read_measure_max(&self, measure: &str) -> Option<f64> {
+        self.read_measure_number(measure, "MaxValue")
+    }
+
+    /// `MinValue` of `measure` (`[measure:MinValue]`).
+    pub fn read_measure_min(&self, measure: &str) -> Option<f64> {
+        self.read_measure_number(measure, "MinValue")
+    }
+
+    /// Read a `|`-separated list of measure names, e.g.
+    /// `Sources=MeasureCPU1 | MeasureCPU2`, for use with
+    /// [`aggregate`](Self::aggregate).
+    pub fn read_sources(&self, key: &str) -> Vec<String> {
+        self.read_string_list(key, '|')
+    }
+
+    /// Read an [`Aggregate`] option like `Op=avg`. Unknown values are logged
+    /// and give `default`.
+    pub fn read_aggregate(&self, key: &str, default: Aggregate) -> Aggregate {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return default;
+        }
+        Aggregate::parse(&value).unwrap_or_else(|| {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: unknown operation '{}'", key, value),
+            );
+            default
+        })
+    }
+
+    /// Combine the current values of the `sources` measures with `op`.
+    ///
+    /// Measures that can't be resolved (unknown, or not updated yet) are left
+    /// out, and `0.0` is returned if none resolve. Values are read through
+    /// section variables, so the calling measure needs `DynamicVariables=1`
+    /// to see them change.
+    ///
+    /// ```rust,ignore
+    /// fn update(&mut self, rm: RainmeterContext) -> f64 {
+    ///     rm.aggregate(&self.sources, self.op)
+    /// }
+    /// ```
+    pub fn aggregate(&self, sources: &[String], op: Aggregate) -> f64 {
+        let values: Vec<f64> = sources
+            .iter()
+            .filter_map(|source| self.read_measure_value(source))
+            .collect();
+        op.apply(&values).unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn reads_values_through_section_variables() {
+        let rm = MockContext::new();
+        rm.set_variable("[MeasureCPU:]", "42.5")
+            .set_variable("[MeasureCPU:MaxValue]", "100")
+            .set_variable("[MeasureCPU:MinValue]", " -5 ")
+            .set_variable("[MeasureName:]", "n/a");
+        assert_eq!(rm.read_measure_value(" MeasureCPU "), Some(42.5));
+        assert_eq!(rm.read_measure_max("MeasureCPU"), Some(100.0));
+        assert_eq!(rm.read_measure_min("MeasureCPU"), Some(-5.0));
+        // Not a number, and not resolved at all.
+        assert_eq!(rm.read_measure_value("MeasureName"), None);
+        assert_eq!(rm.read_measure_value("MeasureMissing"), None);
+    }
+
+    #[test]
+    fn aggregates_measure_values() {
+        let rm = MockContext::new();
+        rm.set_variable("[MeasureCPU1:]", "10")
+            .set_variable("[MeasureCPU2:]", "30")
+            .set_variable("[MeasureCPU3:]", "-5")
+            .set(
+                "Sources",
+                "MeasureCPU1 | MeasureCPU2 | MeasureMissing | MeasureCPU3",
+            );
+        let sources = rm.read_sources("Sources");
+        assert_eq!(sources.len(), 4);
+        for (op, expected) in [
+            (Aggregate::Sum, 35.0),
+            (Aggregate::Avg, 35.0 / 3.0),
+            (Aggregate::Min, -5.0),
+            (Aggregate::Max, 30.0),
+        ] {
+            assert_eq!(rm.aggregate(&sources, op), expected, "{:?}", op);
+        }
+    }
+
+    #[test]
+    fn aggregating_nothing_gives_zero() {
+        let rm = MockContext::new();
+        assert_eq!(rm.aggregate(&[], Aggregate::Max), 0.0);
+        assert_eq!(
+            rm.aggregate(&["MeasureMissing".to_string()], Aggregate::Min),
+            0.0
+        );
+        assert_eq!(Aggregate::Avg.apply(&[]), None);
+    }
+
+    #[test]
+    fn reads_aggregate_operations() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_aggregate("Op", Aggregate::Sum), Aggregate::Sum);
+        rm.set("Op", "Average");
+        assert_eq!(rm.read_aggregate("Op", Aggregate::Sum), Aggregate::Avg);
+        rm.set("Op", "median");
+        assert_eq!(rm.read_aggregate("Op", Aggregate::Sum), Aggregate::Sum);
+        assert_eq!(
+            rm.warnings(),
+            ["Op: unknown operation 'median'".to_string()]
+        );
+    }
+}
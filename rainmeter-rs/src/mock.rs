@@ -0,0 +1,321 @@
+//! An in-memory stand-in for the Rainmeter API, so unit tests can run
+//! without Rainmeter. `lib.rs` uses the functions in [`ffi`] in place of
+//! `rainmeter_sys` when testing.
+//!
+//! State is per thread, so tests running in parallel don't see each other's
+//! options or logs. Things the plugin does on other threads (logging from a
+//! worker, say) land in that thread's state.
+
+// Not every test module uses every setter.
+#![allow(dead_code)]
+
+use crate::RainmeterContext;
+use std::cell::RefCell;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Hands out a distinct fake `rm` pointer per context, so state the crate
+/// keys by measure (tracing, the debug overlay) doesn't leak between tests.
+static NEXT_RM: AtomicUsize = AtomicUsize::new(0x1000);
+
+#[derive(Default)]
+struct State {
+    /// `(section, key, value)`; the measure's own options use its name.
+    options: Vec<(String, String, String)>,
+    /// `(token, value)` substitutions such as `("#Color#", "255,0,0")`.
+    variables: Vec<(String, String)>,
+    logs: Vec<(i32, String)>,
+    bangs: Vec<String>,
+    measure_name: String,
+    skin_path: PathBuf,
+    skin_window: usize,
+    /// Strings handed back to the crate. Kept alive until the next reset,
+    /// like Rainmeter keeps its return buffers alive until the next call.
+    strings: Vec<Vec<u16>>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+fn with_state<T>(f: impl FnOnce(&mut State) -> T) -> T {
+    STATE.with(|state| f(&mut state.borrow_mut()))
+}
+
+/// A [`RainmeterContext`] backed by the mock, with setters for what the
+/// skin would provide. Derefs to the context.
+///
+/// Creating one resets the thread's mock state: no options, no logs and a
+/// measure called `MeasureTest` in a skin at the system temp directory.
+pub(crate) struct MockContext {
+    rm: RainmeterContext,
+}
+
+impl MockContext {
+    pub(crate) fn new() -> Self {
+        with_state(|state| {
+            *state = State {
+                measure_name: "MeasureTest".to_string(),
+                skin_path: std::env::temp_dir(),
+                ..State::default()
+            }
+        });
+        let raw = NEXT_RM.fetch_add(16, Ordering::Relaxed);
+        Self {
+            rm: RainmeterContext::new(raw as *mut _),
+        }
+    }
+
+    /// Another context for the same measure, for APIs that take one by
+    /// value.
+    pub(crate) fn context(&self) -> RainmeterContext {
+        RainmeterContext::new(self.rm.raw)
+    }
+
+    /// Set an option on the measure, replacing any earlier value.
+    pub(crate) fn set(&self, key: &str, value: &str) -> &Self {
+        let measure = with_state(|state| state.measure_name.clone());
+        self.set_in(&measure, key, value)
+    }
+
+    /// Set an option in another section of the skin.
+    pub(crate) fn set_in(&self, section: &str, key: &str, value: &str) -> &Self {
+        with_state(|state| {
+            state.options.retain(|(s, k, _)| {
+                !(s.eq_ignore_ascii_case(section) && k.eq_ignore_ascii_case(key))
+            });
+            state
+                .options
+                .push((section.to_string(), key.to_string(), value.to_string()));
+        });
+        self
+    }
+
+    /// Remove an option from the measure.
+    pub(crate) fn unset(&self, key: &str) -> &Self {
+        with_state(|state| {
+            let measure = state.measure_name.clone();
+            state.options.retain(|(s, k, _)| {
+                !(s.eq_ignore_ascii_case(&measure) && k.eq_ignore_ascii_case(key))
+            });
+        });
+        self
+    }
+
+    /// Make `token` (written in full, e.g. `#Color#` or `[MeasureCPU]`)
+    /// expand to `value`. Section variables (`[...]`) are left alone by
+    /// `read_string_raw`, like Rainmeter does.
+    pub(crate) fn set_variable(&self, token: &str, value: &str) -> &Self {
+        with_state(|state| {
+            state.variables.retain(|(t, _)| t != token);
+            state.variables.push((token.to_string(), value.to_string()));
+        });
+        self
+    }
+
+    pub(crate) fn set_measure_name(&self, name: &str) -> &Self {
+        with_state(|state| state.measure_name = name.to_string());
+        self
+    }
+
+    /// The directory relative paths are resolved against.
+    pub(crate) fn set_skin_path(&self, path: &Path) -> &Self {
+        with_state(|state| state.skin_path = path.to_path_buf());
+        self
+    }
+
+    pub(crate) fn set_skin_window(&self, hwnd: usize) -> &Self {
+        with_state(|state| state.skin_window = hwnd);
+        self
+    }
+
+    /// Everything logged so far, as `(level, message)`.
+    pub(crate) fn logs(&self) -> Vec<(i32, String)> {
+        with_state(|state| state.logs.clone())
+    }
+
+    /// Messages logged at `LogWarning` or `LogError`.
+    pub(crate) fn warnings(&self) -> Vec<String> {
+        with_state(|state| {
+            state
+                .logs
+                .iter()
+                .filter(|(level, _)| *level <= 2)
+                .map(|(_, message)| message.clone())
+                .collect()
+        })
+    }
+
+    pub(crate) fn clear_logs(&self) {
+        with_state(|state| state.logs.clear());
+    }
+
+    /// Every command passed to `RmExecute` so far.
+    pub(crate) fn bangs(&self) -> Vec<String> {
+        with_state(|state| state.bangs.clone())
+    }
+}
+
+impl Deref for MockContext {
+    type Target = RainmeterContext;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rm
+    }
+}
+
+/// Drop-in replacements for the `rainmeter_sys` functions the crate calls.
+#[allow(non_snake_case)]
+pub(crate) mod ffi {
+    use super::{State, with_state};
+    use std::ffi::c_void;
+    use std::path::Path;
+
+    unsafe fn string(ptr: *const u16) -> String {
+        if ptr.is_null() {
+            return String::new();
+        }
+        let mut len = 0;
+        while unsafe { *ptr.add(len) } != 0 {
+            len += 1;
+        }
+        String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(ptr, len) })
+    }
+
+    fn hand_out(state: &mut State, value: &str) -> *const u16 {
+        let wide: Vec<u16> = value.encode_utf16().chain(Some(0)).collect();
+        let ptr = wide.as_ptr();
+        state.strings.push(wide);
+        ptr
+    }
+
+    fn lookup(state: &State, section: &str, key: &str) -> Option<String> {
+        state
+            .options
+            .iter()
+            .find(|(s, k, _)| s.eq_ignore_ascii_case(section) && k.eq_ignore_ascii_case(key))
+            .map(|(_, _, value)| value.clone())
+    }
+
+    fn substitute(state: &State, mut text: String, sections: bool) -> String {
+        for (token, value) in &state.variables {
+            if sections || !token.starts_with('[') {
+                text = text.replace(token.as_str(), value);
+            }
+        }
+        text
+    }
+
+    fn read_string(
+        section: Option<*const u16>,
+        option: *const u16,
+        default: *const u16,
+        replace: i32,
+    ) -> *const u16 {
+        with_state(|state| {
+            let section = match section {
+                Some(section) => unsafe { string(section) },
+                None => state.measure_name.clone(),
+            };
+            let value = lookup(state, &section, &unsafe { string(option) })
+                .unwrap_or_else(|| unsafe { string(default) });
+            let value = substitute(state, value, replace != 0);
+            hand_out(state, &value)
+        })
+    }
+
+    fn read_formula(section: Option<*const u16>, option: *const u16, default: f64) -> f64 {
+        with_state(|state| {
+            let section = match section {
+                Some(section) => unsafe { string(section) },
+                None => state.measure_name.clone(),
+            };
+            lookup(state, &section, &unsafe { string(option) })
+                .map(|value| substitute(state, value, true))
+                .and_then(|value| value.trim().parse().ok())
+                .unwrap_or(default)
+        })
+    }
+
+    pub(crate) unsafe fn RmReadString(
+        _rm: *mut c_void,
+        option: *const u16,
+        default: *const u16,
+        replace_measures: i32,
+    ) -> *const u16 {
+        read_string(None, option, default, replace_measures)
+    }
+
+    pub(crate) unsafe fn RmReadStringFromSection(
+        _rm: *mut c_void,
+        section: *const u16,
+        option: *const u16,
+        default: *const u16,
+        replace_measures: i32,
+    ) -> *const u16 {
+        read_string(Some(section), option, default, replace_measures)
+    }
+
+    pub(crate) unsafe fn RmReadFormula(_rm: *mut c_void, option: *const u16, default: f64) -> f64 {
+        read_formula(None, option, default)
+    }
+
+    pub(crate) unsafe fn RmReadFormulaFromSection(
+        _rm: *mut c_void,
+        section: *const u16,
+        option: *const u16,
+        default: f64,
+    ) -> f64 {
+        read_formula(Some(section), option, default)
+    }
+
+    pub(crate) unsafe fn RmReplaceVariables(_rm: *mut c_void, text: *const u16) -> *const u16 {
+        with_state(|state| {
+            let text = substitute(state, unsafe { string(text) }, true);
+            hand_out(state, &text)
+        })
+    }
+
+    pub(crate) unsafe fn RmPathToAbsolute(_rm: *mut c_void, path: *const u16) -> *const u16 {
+        with_state(|state| {
+            let path = unsafe { string(path) };
+            let absolute = if Path::new(&path).is_absolute() {
+                path
+            } else {
+                state.skin_path.join(path).to_string_lossy().into_owned()
+            };
+            hand_out(state, &absolute)
+        })
+    }
+
+    pub(crate) unsafe fn RmExecute(_skin: *mut c_void, command: *const u16) {
+        let command = unsafe { string(command) };
+        with_state(|state| state.bangs.push(command));
+    }
+
+    pub(crate) unsafe fn RmGet(rm: *mut c_void, what: i32) -> *mut c_void {
+        with_state(|state| match what {
+            0 => {
+                let name = state.measure_name.clone();
+                hand_out(state, &name) as *mut c_void
+            }
+            // Any non-null pointer will do as the skin.
+            1 => rm,
+            // Like Rainmeter's, the settings file is the same for every
+            // measure on every thread.
+            2 => {
+                let settings = std::env::temp_dir().join("Rainmeter.ini");
+                hand_out(state, &settings.to_string_lossy()) as *mut c_void
+            }
+            3 => hand_out(state, "illustro\\Mock") as *mut c_void,
+            4 => state.skin_window as *mut c_void,
+            _ => std::ptr::null_mut(),
+        })
+    }
+
+    pub(crate) unsafe fn RmLog(_rm: *mut c_void, level: i32, message: *const u16) {
+        let message = unsafe { string(message) };
+        with_state(|state| state.logs.push((level, message)));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{RainmeterContext, RmError};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Shell::{
+    NIF_ICON, NIF_INFO, NIIF_INFO, NIM_ADD, NIM_DELETE, NIM_MODIFY, NOTIFYICONDATAW,
+    Shell_NotifyIconW,
+};
+use windows::Win32::UI::WindowsAndMessaging::{IDI_INFORMATION, LoadIconW};
+
+/// Tray icon id used for plugin notifications. Rainmeter keys tray icons by
+/// `(HWND, uID)`, so this only has to be unique per skin window.
+const NOTIFY_ICON_ID: u32 = 0x524D_5253; // "RMRS"
+
+/// Copy `src` into a fixed-size, NUL-terminated UTF-16 buffer, truncating if needed.
+fn copy_wide<const N: usize>(dst: &mut [u16; N], src: &str) {
+    let mut len = 0;
+    for (slot, unit) in dst.iter_mut().take(N - 1).zip(src.encode_utf16()) {
+        *slot = unit;
+        len += 1;
+    }
+    dst[len] = 0;
+}
+
+/// Build the balloon `NOTIFYICONDATAW` for the given skin window.
+fn notify_icon_data(hwnd: HWND, title: &str, body: &str) -> NOTIFYICONDATAW {
+    let mut data = NOTIFYICONDATAW {
+        cbSize: size_of::<NOTIFYICONDATAW>() as u32,
+        hWnd: hwnd,
+        uID: NOTIFY_ICON_ID,
+        uFlags: NIF_INFO,
+        dwInfoFlags: NIIF_INFO,
+        ..Default::default()
+    };
+    copy_wide(&mut data.szInfoTitle, title);
+    copy_wide(&mut data.szInfo, body);
+    data
+}
+
+impl RainmeterContext {
+    /// Show a balloon notification attached to the skin window.
+    ///
+    /// Rainmeter has no bang for notifications, so this adds a tray icon owned
+    /// by the skin window via `Shell_NotifyIconW` and pops a balloon from it.
+    /// On Windows 10+ the balloon is shown as a toast in the Action Center.
+    /// The skin window must exist (so don't call this before `initialize`), and
+    /// the plugin should call [`clear_notification`](Self::clear_notification)
+    /// in `finalize` to remove the tray icon again.
+    /// Title and body are truncated to 63 and 255 UTF-16 units respectively.
+    pub fn show_notification(&self, title: &str, body: &str) -> Result<(), RmError> {
+        let mut data = notify_icon_data(self.get_skin_window(), title, body);
+        data.uFlags |= NIF_ICON;
+        data.hIcon = unsafe { LoadIconW(None, IDI_INFORMATION) }?;
+        unsafe {
+            // The icon may already be there from an earlier notification.
+            if !Shell_NotifyIconW(NIM_MODIFY, &data).as_bool() {
+                Shell_NotifyIconW(NIM_ADD, &data).ok()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the tray icon added by [`show_notification`](Self::show_notification), if any.
+    pub fn clear_notification(&self) {
+        let data = notify_icon_data(self.get_skin_window(), "", "");
+        unsafe {
+            let _ = Shell_NotifyIconW(NIM_DELETE, &data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    fn text(units: &[u16]) -> String {
+        let len = units.iter().position(|&u| u == 0).unwrap();
+        String::from_utf16(&units[..len]).unwrap()
+    }
+
+    #[test]
+    fn balloon_targets_the_skin_window() {
+        let rm = MockContext::new();
+        rm.set_skin_window(0x1234);
+        let data = notify_icon_data(rm.get_skin_window(), "Weather", "Rain in 10 minutes");
+        assert_eq!(data.hWnd, HWND(0x1234 as *mut _));
+        assert_eq!(data.uID, NOTIFY_ICON_ID);
+        assert_eq!(data.uFlags, NIF_INFO);
+        assert_eq!(data.dwInfoFlags, NIIF_INFO);
+        assert_eq!(text(&data.szInfoTitle), "Weather");
+        assert_eq!(text(&data.szInfo), "Rain in 10 minutes");
+    }
+
+    #[test]
+    fn long_text_is_truncated() {
+        let data = notify_icon_data(HWND::default(), &"t".repeat(100), &"b".repeat(300));
+        assert_eq!(text(&data.szInfoTitle), "t".repeat(63));
+        assert_eq!(text(&data.szInfo), "b".repeat(255));
+    }
+}
@@ -0,0 +1,758 @@
+//! Higher-level option readers built on top of the basic `read_*` methods.
+
+use crate::{RainmeterContext, RmLogLevel};
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Split `value` on `sep`, trimming entries and dropping empty ones.
+pub(crate) fn split_list(value: &str, sep: char) -> Vec<String> {
+    value
+        .split(sep)
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Resolve `\n`, `\t` and `\\` escapes. Other backslashes are kept, so
+/// Windows paths in the text survive unless they happen to contain `\n`.
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('\\') => out.push('\\'),
+            _ => {
+                out.push('\\');
+                continue;
+            }
+        }
+        chars.next();
+    }
+    out
+}
+
+/// Decode `%XX` escapes and `+` (as a space) in URL-encoded text. A `%`
+/// not followed by two hex digits is kept as is, and decoded bytes that
+/// aren't valid UTF-8 become U+FFFD.
+///
+/// ```rust
+/// # use rainmeter::percent_decode;
+/// assert_eq!(percent_decode("Hello+World%21"), "Hello World!");
+/// assert_eq!(percent_decode("caf%C3%A9%20%26%20bar"), "café & bar");
+/// assert_eq!(percent_decode("100% sure, %zz, %4"), "100% sure, %zz, %4");
+/// ```
+pub fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = |offset: usize| (*bytes.get(i + offset)? as char).to_digit(16);
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => match (hex(1), hex(2)) {
+                (Some(high), Some(low)) => {
+                    out.push((high * 16 + low) as u8);
+                    i += 2;
+                }
+                _ => out.push(b'%'),
+            },
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Look `value` up in `table`, ignoring case and surrounding whitespace.
+pub(crate) fn lookup_mapped<T: Clone>(value: &str, table: &[(&str, T)]) -> Option<T> {
+    let value = value.trim();
+    table
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(value))
+        .map(|(_, v)| v.clone())
+}
+
+/// An enum stored in options by name. The one [`NAMES`](Self::NAMES) table
+/// serves both [`read_enum`](RainmeterContext::read_enum) and
+/// [`set_mode_option`](RainmeterContext::set_mode_option), so a mode read
+/// from the skin is written back under the same name.
+///
+/// ```rust
+/// # use rainmeter::OptionEnum;
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Mode {
+///     Compact,
+///     Detailed,
+/// }
+///
+/// impl OptionEnum for Mode {
+///     const NAMES: &'static [(&'static str, Self)] = &[
+///         ("Compact", Mode::Compact),
+///         ("Detailed", Mode::Detailed),
+///         ("Full", Mode::Detailed),
+///     ];
+/// }
+///
+/// assert_eq!(Mode::from_name("full"), Some(Mode::Detailed));
+/// assert_eq!(Mode::Detailed.name(), Some("Detailed"));
+/// let name = Mode::Compact.name().unwrap();
+/// assert_eq!(Mode::from_name(name), Some(Mode::Compact));
+/// ```
+pub trait OptionEnum: Clone + PartialEq + 'static {
+    /// `(name, value)` pairs. Names are matched ignoring case when reading;
+    /// aliases are allowed, and the first name listed for a value is the
+    /// one written.
+    const NAMES: &'static [(&'static str, Self)];
+
+    fn from_name(name: &str) -> Option<Self> {
+        lookup_mapped(name, Self::NAMES)
+    }
+
+    /// The name written for this value, or `None` if it isn't in
+    /// [`NAMES`](Self::NAMES).
+    fn name(&self) -> Option<&'static str> {
+        Self::NAMES
+            .iter()
+            .find(|(_, value)| value == self)
+            .map(|&(name, _)| name)
+    }
+}
+
+impl RainmeterContext {
+    /// Read an option and map it through a `(name, value)` table, matching
+    /// names case-insensitively. Returns `default` if the option is missing
+    /// or doesn't match any entry; values that don't match are logged.
+    ///
+    /// ```rust,ignore
+    /// let quality = rm.read_mapped("Quality", &[("Low", 0), ("Medium", 1), ("High", 2)], 1);
+    /// ```
+    pub fn read_mapped<T: Clone>(&self, key: &str, table: &[(&str, T)], default: T) -> T {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return default;
+        }
+        lookup_mapped(&value, table).unwrap_or_else(|| {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: unknown value '{}'", key, value),
+            );
+            default
+        })
+    }
+
+    /// Read an [`OptionEnum`] by name, e.g. `Mode=Detailed`. Unknown names
+    /// are logged and give `default`.
+    pub fn read_enum<T: OptionEnum>(&self, key: &str, default: T) -> T {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return default;
+        }
+        T::from_name(&value).unwrap_or_else(|| {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: unknown value '{}'", key, value),
+            );
+            default
+        })
+    }
+
+    /// Write `mode` back to `option` of `section` with `!SetOption`, under
+    /// the name [`read_enum`](Self::read_enum) reads, e.g. after a bang
+    /// switched modes. As with any `!SetOption`, the change is lost when the
+    /// skin is refreshed, and `reload` only sees it with `DynamicVariables=1`.
+    ///
+    /// ```rust,ignore
+    /// fn execute_bang(&mut self, rm: RainmeterContext, args: &str) {
+    ///     if args.eq_ignore_ascii_case("ToggleMode") {
+    ///         self.mode = if self.mode == Mode::Compact { Mode::Detailed } else { Mode::Compact };
+    ///         rm.set_mode_option(&rm.get_measure_name(), "Mode", self.mode);
+    ///     }
+    /// }
+    /// ```
+    pub fn set_mode_option<T: OptionEnum>(&self, section: &str, option: &str, mode: T) {
+        match mode.name() {
+            Some(name) => self.execute(&format!(
+                "[!SetOption \"{}\" \"{}\" \"{}\"]",
+                section, option, name
+            )),
+            None => self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: value has no name to write", option),
+            ),
+        }
+    }
+
+    /// Read an option that may be given either by name or by zero-based
+    /// index, like `Alignment=Center` or `Alignment=1`. Names in `by_name` are
+    /// tried first (ignoring case), then the value is parsed as an index into
+    /// `by_index`. Unknown names and out-of-range indices are logged and give
+    /// `default`.
+    ///
+    /// ```rust,ignore
+    /// let align = rm.read_enum_flexible(
+    ///     "Alignment",
+    ///     &[("Left", Align::Left), ("Center", Align::Center), ("Right", Align::Right)],
+    ///     &[Align::Left, Align::Center, Align::Right],
+    ///     Align::Left,
+    /// );
+    /// ```
+    pub fn read_enum_flexible<T: Clone>(
+        &self,
+        key: &str,
+        by_name: &[(&str, T)],
+        by_index: &[T],
+        default: T,
+    ) -> T {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return default;
+        }
+        if let Some(found) = lookup_mapped(&value, by_name) {
+            return found;
+        }
+        if let Some(found) = value
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| by_index.get(index))
+        {
+            return found.clone();
+        }
+        self.log(
+            RmLogLevel::LogWarning,
+            &format!("{}: unknown value '{}'", key, value),
+        );
+        default
+    }
+
+    /// Read an option that accepts synonyms and return its canonical value.
+    /// `aliases` maps each alias to a canonical value, e.g.
+    /// `&[("c", "Celsius"), ("celsius", "Celsius"), ("f", "Fahrenheit")]`;
+    /// canonical values themselves are accepted too. Matching ignores case.
+    /// Unknown values are logged and give `default`.
+    pub fn read_alias(&self, key: &str, aliases: &[(&str, &str)], default: &str) -> String {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return default.to_string();
+        }
+        let canonical = lookup_mapped(&value, aliases).or_else(|| {
+            let value = value.trim();
+            aliases
+                .iter()
+                .map(|(_, canonical)| *canonical)
+                .find(|canonical| canonical.eq_ignore_ascii_case(value))
+        });
+        match canonical {
+            Some(canonical) => canonical.to_string(),
+            None => {
+                self.log(
+                    RmLogLevel::LogWarning,
+                    &format!("{}: unknown value '{}', using '{}'", key, value, default),
+                );
+                default.to_string()
+            }
+        }
+    }
+
+    /// Read a boolean option and flip it if another boolean option is set,
+    /// for `Invert=1` style toggles: `value_key` XOR `invert_key`. Both
+    /// default to `false`.
+    pub fn read_bool_maybe_inverted(&self, value_key: &str, invert_key: &str) -> bool {
+        self.read_bool(value_key, false) != self.read_bool(invert_key, false)
+    }
+
+    /// Read a multi-line text option. INI values can't span lines, so the
+    /// text is either given inline with `\n` (and `\t`, `\\`) escapes, e.g.
+    /// `Text=First line\nSecond line`, or loaded from a file with
+    /// `Text=@file:Notes.txt` (resolved relative to the skin). File contents
+    /// are used verbatim, minus a UTF-8 byte order mark.
+    ///
+    /// Returns `default` if the option is missing; unreadable files are
+    /// logged and give `default` too.
+    pub fn read_text_block(&self, key: &str, default: &str) -> String {
+        let value = self.read_string(key, "");
+        if value.is_empty() {
+            return default.to_string();
+        }
+        let Some(file) = value.trim_start().strip_prefix("@file:") else {
+            return unescape_text(&value);
+        };
+        let path = self.path_to_absolute(file.trim());
+        match std::fs::read(&path) {
+            Ok(bytes) => {
+                let text = String::from_utf8_lossy(&bytes);
+                text.strip_prefix('\u{feff}').unwrap_or(&text).to_string()
+            }
+            Err(err) => {
+                self.log(
+                    RmLogLevel::LogWarning,
+                    &format!("{}: can't read '{}': {}", key, path, err),
+                );
+                default.to_string()
+            }
+        }
+    }
+
+    /// Read a URL-encoded option, e.g. `Query=name%3DJohn+Doe%26x%3D1`, and
+    /// decode it with [`percent_decode`].
+    pub fn read_percent_decoded(&self, key: &str) -> String {
+        percent_decode(&self.read_string(key, ""))
+    }
+
+    /// Read a `sep`-separated list option (e.g. `Items=a | b | c`), trimming
+    /// whitespace around entries and dropping empty ones.
+    pub fn read_string_list(&self, key: &str, sep: char) -> Vec<String> {
+        split_list(&self.read_string(key, ""), sep)
+    }
+
+    /// Like [`read_string_list`](Self::read_string_list), but drops repeated
+    /// entries, keeping the first occurrence of each. Matching is exact
+    /// (case-sensitive) after trimming.
+    pub fn read_unique_list(&self, key: &str, sep: char) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.read_string_list(key, sep)
+            .into_iter()
+            .filter(|entry| seen.insert(entry.clone()))
+            .collect()
+    }
+
+    /// Pick one entry from a list option by a zero-based index option, e.g.
+    /// `Items=a|b|c` with `Index=1` gives `b`. The index may be a formula, so
+    /// it can follow another measure. Returns `None` if the index is missing,
+    /// negative or past the end of the list.
+    pub fn read_indexed_selection(
+        &self,
+        items_key: &str,
+        index_key: &str,
+        sep: char,
+    ) -> Option<String> {
+        let index = self.read_formula(index_key, -1.0);
+        if index.is_nan() || index < 0.0 {
+            return None;
+        }
+        self.read_string_list(items_key, sep)
+            .into_iter()
+            .nth(index as usize)
+    }
+
+    /// Read a `sep`-separated list and parse each entry with `parse_entry`.
+    /// Entries the parser rejects are logged and skipped, so one typo doesn't
+    /// throw away the whole list.
+    ///
+    /// ```rust,ignore
+    /// let sizes: Vec<u32> = rm.read_list_schema("Sizes", ',', |e| e.parse().ok());
+    /// ```
+    pub fn read_list_schema<T>(
+        &self,
+        key: &str,
+        sep: char,
+        parse_entry: impl Fn(&str) -> Option<T>,
+    ) -> Vec<T> {
+        self.read_string_list(key, sep)
+            .into_iter()
+            .filter_map(|entry| {
+                let parsed = parse_entry(&entry);
+                if parsed.is_none() {
+                    self.log(
+                        RmLogLevel::LogWarning,
+                        &format!("{}: skipping invalid entry '{}'", key, entry),
+                    );
+                }
+                parsed
+            })
+            .collect()
+    }
+
+    /// Read a number (or formula) and clamp it to `[min, max]`. Out-of-range
+    /// values are logged before clamping; a missing option gives `default`,
+    /// which is not checked.
+    pub fn read_clamped(&self, key: &str, default: f64, min: f64, max: f64) -> f64 {
+        let value = self.read_formula(key, default);
+        if value.is_nan() {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: value is not a number, using {}", key, default),
+            );
+            return default;
+        }
+        if value < min || value > max {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: {} is outside [{}, {}], clamping", key, value, min, max),
+            );
+        }
+        value.clamp(min, max)
+    }
+
+    /// Integer version of [`read_clamped`](Self::read_clamped).
+    pub fn read_clamped_int(&self, key: &str, default: i32, min: i32, max: i32) -> i32 {
+        self.read_clamped(key, default as f64, min as f64, max as f64) as i32
+    }
+
+    /// Hash the current values of `keys` into a single fingerprint.
+    ///
+    /// Compare fingerprints between updates (with `DynamicVariables=1`) to
+    /// skip expensive reconfiguration when nothing changed. Fingerprints are
+    /// only stable within a single Rainmeter session, so don't persist them.
+    pub fn options_fingerprint(&self, keys: &[&str]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for key in keys {
+            key.hash(&mut hasher);
+            self.read_string(key, "").hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Name of the section holding the active profile's options, taken from
+    /// the `profile_key` option (e.g. `Profile=Work` selects section `[Work]`).
+    /// Returns an empty string if no profile is selected.
+    pub fn read_profile_section(&self, profile_key: &str) -> String {
+        self.read_string(profile_key, "").trim().to_string()
+    }
+
+    /// Read `option` from the active profile's section (see
+    /// [`read_profile_section`](Self::read_profile_section)), or `default` if
+    /// no profile is selected or the profile doesn't set it.
+    pub fn read_from_profile(&self, profile_key: &str, option: &str, default: &str) -> String {
+        let section = self.read_profile_section(profile_key);
+        if section.is_empty() {
+            return default.to_string();
+        }
+        self.read_string_section(&section, option, default)
+    }
+
+    /// Read an option holding a base64-encoded (standard alphabet, padded) blob.
+    #[cfg(feature = "base64")]
+    pub fn read_base64(&self, key: &str) -> Result<Vec<u8>, crate::RmError> {
+        use base64::Engine;
+        let value = self.read_string(key, "");
+        base64::engine::general_purpose::STANDARD
+            .decode(value.trim())
+            .map_err(|err| crate::RmError::ParseFailed {
+                key: key.to_string(),
+                message: err.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn fingerprint_is_stable_until_an_option_changes() {
+        let rm = MockContext::new();
+        rm.set("Url", "https://example.com").set("Interval", "60");
+        let keys = ["Url", "Interval"];
+
+        let first = rm.options_fingerprint(&keys);
+        assert_eq!(rm.options_fingerprint(&keys), first);
+
+        rm.set("Interval", "30");
+        assert_ne!(rm.options_fingerprint(&keys), first);
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn base64_options_decode() {
+        let rm = MockContext::new();
+        rm.set("Payload", " aGVsbG8= ");
+        assert_eq!(rm.read_base64("Payload").unwrap(), b"hello");
+        rm.set("Payload", "");
+        assert_eq!(rm.read_base64("Payload").unwrap(), b"");
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn invalid_base64_is_a_parse_error() {
+        let rm = MockContext::new();
+        rm.set("Payload", "not base64!");
+        match rm.read_base64("Payload") {
+            Err(crate::RmError::ParseFailed { key, .. }) => assert_eq!(key, "Payload"),
+            other => panic!("expected ParseFailed, got {:?}", other),
+        }
+    }
+
+    const QUALITY: &[(&str, i32)] = &[("Low", 0), ("Medium", 1), ("High", 2)];
+
+    #[test]
+    fn mapped_options_match_ignoring_case() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_mapped("Quality", QUALITY, 1), 1);
+        rm.set("Quality", "High");
+        assert_eq!(rm.read_mapped("Quality", QUALITY, 1), 2);
+        rm.set("Quality", " low ");
+        assert_eq!(rm.read_mapped("Quality", QUALITY, 1), 0);
+        assert!(rm.warnings().is_empty());
+    }
+
+    #[test]
+    fn unknown_mapped_values_give_the_default_and_warn() {
+        let rm = MockContext::new();
+        rm.set("Quality", "Ultra");
+        assert_eq!(rm.read_mapped("Quality", QUALITY, 1), 1);
+        assert_eq!(rm.warnings(), ["Quality: unknown value 'Ultra'"]);
+    }
+
+    #[test]
+    fn string_lists_are_trimmed_without_empty_entries() {
+        let rm = MockContext::new();
+        assert!(rm.read_string_list("Items", '|').is_empty());
+        rm.set("Items", " a | b c ||  | d");
+        assert_eq!(rm.read_string_list("Items", '|'), ["a", "b c", "d"]);
+    }
+
+    #[test]
+    fn reads_from_the_selected_profile() {
+        let rm = MockContext::new();
+        rm.set_in("Work", "Url", "https://work.example").set_in(
+            "Home",
+            "Url",
+            "https://home.example",
+        );
+        assert_eq!(rm.read_profile_section("Profile"), "");
+        assert_eq!(rm.read_from_profile("Profile", "Url", "none"), "none");
+
+        rm.set("Profile", " Work ");
+        assert_eq!(rm.read_profile_section("Profile"), "Work");
+        assert_eq!(
+            rm.read_from_profile("Profile", "Url", "none"),
+            "https://work.example"
+        );
+        rm.set("Profile", "Home");
+        assert_eq!(
+            rm.read_from_profile("Profile", "Url", "none"),
+            "https://home.example"
+        );
+        assert_eq!(rm.read_from_profile("Profile", "Interval", "60"), "60");
+        rm.set("Profile", "Travel");
+        assert_eq!(rm.read_from_profile("Profile", "Url", "none"), "none");
+    }
+
+    #[test]
+    fn list_schema_parses_and_skips_bad_entries() {
+        let rm = MockContext::new();
+        rm.set("Sizes", "16, 32, big, 64, -1");
+        let sizes: Vec<u32> = rm.read_list_schema("Sizes", ',', |entry| entry.parse().ok());
+        assert_eq!(sizes, [16, 32, 64]);
+        assert_eq!(
+            rm.warnings(),
+            [
+                "Sizes: skipping invalid entry 'big'",
+                "Sizes: skipping invalid entry '-1'",
+            ]
+        );
+    }
+
+    #[test]
+    fn clamps_out_of_range_values() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_clamped("Alpha", 0.5, 0.0, 1.0), 0.5);
+        rm.set("Alpha", "0.25");
+        assert_eq!(rm.read_clamped("Alpha", 0.5, 0.0, 1.0), 0.25);
+        rm.set("Count", "1");
+        assert_eq!(rm.read_clamped_int("Count", 5, 1, 10), 1);
+        assert!(rm.warnings().is_empty());
+
+        rm.set("Alpha", "-2");
+        assert_eq!(rm.read_clamped("Alpha", 0.5, 0.0, 1.0), 0.0);
+        rm.set("Count", "12");
+        assert_eq!(rm.read_clamped_int("Count", 5, 1, 10), 10);
+        assert_eq!(
+            rm.warnings(),
+            [
+                "Alpha: -2 is outside [0, 1], clamping".to_string(),
+                "Count: 12 is outside [1, 10], clamping".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn unique_list_keeps_first_occurrences() {
+        let rm = MockContext::new();
+        rm.set("Tags", "b | a | b | A |  a  | c");
+        assert_eq!(rm.read_unique_list("Tags", '|'), ["b", "a", "A", "c"]);
+    }
+
+    #[test]
+    fn selects_items_by_index() {
+        let rm = MockContext::new();
+        rm.set("Items", "a | b | c");
+        assert_eq!(rm.read_indexed_selection("Items", "Index", '|'), None);
+        rm.set("Index", "1");
+        assert_eq!(
+            rm.read_indexed_selection("Items", "Index", '|').as_deref(),
+            Some("b")
+        );
+        rm.set("Index", "0");
+        assert_eq!(
+            rm.read_indexed_selection("Items", "Index", '|').as_deref(),
+            Some("a")
+        );
+        rm.set("Index", "3");
+        assert_eq!(rm.read_indexed_selection("Items", "Index", '|'), None);
+        rm.set("Index", "-1");
+        assert_eq!(rm.read_indexed_selection("Items", "Index", '|'), None);
+    }
+
+    #[test]
+    fn reads_aliases() {
+        const UNITS: &[(&str, &str)] = &[("c", "Celsius"), ("°c", "Celsius"), ("f", "Fahrenheit")];
+        let rm = MockContext::new();
+        assert_eq!(rm.read_alias("Units", UNITS, "Celsius"), "Celsius");
+        for (value, expected) in [
+            ("Fahrenheit", "Fahrenheit"),
+            (" celsius ", "Celsius"),
+            ("f", "Fahrenheit"),
+            ("C", "Celsius"),
+        ] {
+            rm.set("Units", value);
+            assert_eq!(rm.read_alias("Units", UNITS, "Celsius"), expected);
+        }
+        assert!(rm.warnings().is_empty());
+
+        rm.set("Units", "Kelvin");
+        assert_eq!(rm.read_alias("Units", UNITS, "Celsius"), "Celsius");
+        assert_eq!(
+            rm.warnings(),
+            ["Units: unknown value 'Kelvin', using 'Celsius'".to_string()]
+        );
+    }
+
+    #[test]
+    fn reads_enums_by_name_or_index() {
+        const NAMES: &[(&str, char)] = &[("Left", 'L'), ("Center", 'C'), ("Right", 'R')];
+        const INDEXED: &[char] = &['L', 'C', 'R'];
+        let rm = MockContext::new();
+        assert_eq!(rm.read_enum_flexible("Align", NAMES, INDEXED, 'L'), 'L');
+        for (value, expected) in [("right", 'R'), (" Center ", 'C'), ("1", 'C'), ("2", 'R')] {
+            rm.set("Align", value);
+            assert_eq!(
+                rm.read_enum_flexible("Align", NAMES, INDEXED, 'L'),
+                expected
+            );
+        }
+        assert!(rm.warnings().is_empty());
+
+        for value in ["3", "-1", "Justify"] {
+            rm.set("Align", value);
+            assert_eq!(rm.read_enum_flexible("Align", NAMES, INDEXED, 'L'), 'L');
+        }
+        assert_eq!(
+            rm.warnings(),
+            [
+                "Align: unknown value '3'".to_string(),
+                "Align: unknown value '-1'".to_string(),
+                "Align: unknown value 'Justify'".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_inline_text_blocks() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_text_block("Text", "none"), "none");
+        rm.set("Text", r"First\nSecond\tTabbed\\n C:\Skins");
+        assert_eq!(
+            rm.read_text_block("Text", "none"),
+            "First\nSecond\tTabbed\\n C:\\Skins"
+        );
+    }
+
+    #[test]
+    fn reads_text_blocks_from_files() {
+        let dir = std::env::temp_dir().join(format!("rainmeter-rs-text-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("Notes.txt"), "\u{feff}Line one\r\nLine \\n two").unwrap();
+        let rm = MockContext::new();
+        rm.set_skin_path(&dir).set("Text", "@file: Notes.txt");
+        // File contents aren't unescaped.
+        assert_eq!(
+            rm.read_text_block("Text", "none"),
+            "Line one\r\nLine \\n two"
+        );
+        assert!(rm.warnings().is_empty());
+
+        rm.set("Text", "@file:Missing.txt");
+        assert_eq!(rm.read_text_block("Text", "none"), "none");
+        let warnings = rm.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].starts_with("Text: can't read '"),
+            "{}",
+            warnings[0]
+        );
+    }
+
+    #[test]
+    fn inverts_booleans() {
+        let rm = MockContext::new();
+        for (value, invert, expected) in [
+            ("0", "0", false),
+            ("1", "0", true),
+            ("0", "1", true),
+            ("1", "1", false),
+        ] {
+            rm.set("Show", value).set("Invert", invert);
+            assert_eq!(
+                rm.read_bool_maybe_inverted("Show", "Invert"),
+                expected,
+                "Show={} Invert={}",
+                value,
+                invert
+            );
+        }
+        rm.unset("Show").unset("Invert");
+        assert!(!rm.read_bool_maybe_inverted("Show", "Invert"));
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Mode {
+        Compact,
+        Detailed,
+        Hidden,
+    }
+
+    impl OptionEnum for Mode {
+        const NAMES: &'static [(&'static str, Self)] = &[
+            ("Compact", Mode::Compact),
+            ("Small", Mode::Compact),
+            ("Detailed", Mode::Detailed),
+        ];
+    }
+
+    #[test]
+    fn modes_round_trip_through_set_option() {
+        let rm = MockContext::new();
+        rm.set("Mode", "small");
+        let mode = rm.read_enum("Mode", Mode::Detailed);
+        assert_eq!(mode, Mode::Compact);
+
+        rm.set_mode_option("MeterInfo", "Mode", mode);
+        rm.set_mode_option("MeterInfo", "Mode", Mode::Detailed);
+        assert_eq!(
+            rm.bangs(),
+            [
+                r#"[!SetOption "MeterInfo" "Mode" "Compact"]"#,
+                r#"[!SetOption "MeterInfo" "Mode" "Detailed"]"#,
+            ]
+        );
+        assert!(rm.warnings().is_empty());
+
+        rm.set_mode_option("MeterInfo", "Mode", Mode::Hidden);
+        assert_eq!(rm.bangs().len(), 2);
+        assert_eq!(
+            rm.warnings(),
+            ["Mode: value has no name to write".to_string()]
+        );
+    }
+}
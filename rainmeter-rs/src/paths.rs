@@ -0,0 +1,287 @@
+//! Option readers that resolve file system paths.
+
+use crate::{RainmeterContext, RmLogLevel};
+use std::path::{Component, Path, PathBuf};
+
+/// Expand `%NAME%` environment variable references. Unknown variables (and
+/// unpaired `%`) are left as-is, matching `ExpandEnvironmentStrings`.
+pub(crate) fn expand_env_vars(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('%') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('%') {
+            Some(end) => {
+                let name = &after[..end];
+                match std::env::var(name) {
+                    Ok(value) if !name.is_empty() => {
+                        out.push_str(&value);
+                        rest = &after[end + 1..];
+                    }
+                    _ => {
+                        // Keep the first `%` literal and retry from the second one,
+                        // which may open a real reference.
+                        out.push('%');
+                        out.push_str(name);
+                        rest = &after[end..];
+                    }
+                }
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Match `name` against a `*`/`?` wildcard pattern, ignoring ASCII case
+/// like the Windows file system does.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let name: Vec<char> = name.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let (mut p, mut n) = (0, 0);
+    // Where the last `*` was, and how much of `name` it has swallowed so far.
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// The files (not directories) in `dir` matching the wildcard `pattern`, sorted by name.
+/// A missing or unreadable directory gives no matches.
+fn glob_dir(dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut matches: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|kind| !kind.is_dir()))
+        .filter(|entry| wildcard_match(pattern, &entry.file_name().to_string_lossy()))
+        .map(|entry| entry.path())
+        .collect();
+    matches.sort();
+    matches
+}
+
+impl RainmeterContext {
+    /// Read a `sep`-separated list of paths, resolve each relative to the skin
+    /// with `path_to_absolute`, and keep only the ones that currently exist.
+    /// Missing entries are skipped with a debug log line.
+    pub fn read_existing_paths(&self, key: &str, sep: char) -> Vec<PathBuf> {
+        self.read_string_list(key, sep)
+            .into_iter()
+            .filter_map(|entry| {
+                let path = PathBuf::from(self.path_to_absolute(&entry));
+                if path.exists() {
+                    Some(path)
+                } else {
+                    self.log(
+                        RmLogLevel::LogDebug,
+                        &format!("{}: skipping missing path '{}'", key, path.display()),
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Read a `sep`-separated list of candidate paths and return the first one
+    /// that exists, e.g. `Path=%USERPROFILE%\Music; D:\Music`.
+    ///
+    /// Rainmeter variables are expanded by `read_string`; `%ENV%` references
+    /// are expanded here. Each candidate is resolved with `path_to_absolute`.
+    pub fn read_first_existing_path(&self, key: &str, sep: char) -> Option<PathBuf> {
+        self.read_string_list(key, sep)
+            .into_iter()
+            .map(|entry| PathBuf::from(self.path_to_absolute(&expand_env_vars(&entry))))
+            .find(|path| path.exists())
+    }
+
+    /// The skin's `@Resources` folder (the `#@#` variable), or `None` if
+    /// Rainmeter can't resolve it, e.g. for skins without a root config.
+    pub fn resources_path(&self) -> Option<PathBuf> {
+        self.resolve_variable("#@#").map(PathBuf::from)
+    }
+
+    /// `name` inside the skin's `@Resources` folder, e.g.
+    /// `rm.resource_file("Images\\icon.png")`. `None` if `#@#` is unresolved.
+    pub fn resource_file(&self, name: &str) -> Option<PathBuf> {
+        Some(self.resources_path()?.join(name))
+    }
+
+    /// Read a `sep`-separated list of paths where entries may use `*` and
+    /// `?` wildcards in their file name, e.g.
+    /// `Sources=Images\*.png | Icons\app.ico`. Each entry is resolved with
+    /// `path_to_absolute`; wildcard entries expand to the matching files
+    /// (sorted by name), and plain entries are kept as-is whether or not they
+    /// exist. Results keep the order of the entries.
+    ///
+    /// Wildcards in directory names (`Images\*\a.png`) aren't expanded;
+    /// such entries are logged and skipped.
+    pub fn read_glob_list(&self, key: &str, sep: char) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        for entry in self.read_string_list(key, sep) {
+            let path = PathBuf::from(self.path_to_absolute(&entry));
+            let name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+            let is_wildcard = |s: &str| s.contains(['*', '?']);
+            // The `\\?\` of a verbatim prefix isn't a wildcard.
+            let dir_has_wildcard = |dir: &Path| {
+                dir.components().any(|component| {
+                    !matches!(component, Component::Prefix(_))
+                        && is_wildcard(&component.as_os_str().to_string_lossy())
+                })
+            };
+            match (path.parent(), name) {
+                (Some(dir), _) if dir_has_wildcard(dir) => {
+                    self.log(
+                        RmLogLevel::LogWarning,
+                        &format!(
+                            "{}: wildcards are only supported in file names: '{}'",
+                            key, entry
+                        ),
+                    );
+                }
+                (Some(dir), Some(name)) if is_wildcard(&name) => paths.extend(glob_dir(dir, &name)),
+                _ => paths.push(path),
+            }
+        }
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    /// A fresh, empty temp directory containing `files`.
+    fn temp_dir(name: &str, files: &[&str]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rainmeter-rs-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in files {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn keeps_only_existing_paths_in_order() {
+        let dir = temp_dir("existing", &["b.txt", "a.txt"]);
+        let rm = MockContext::new();
+        rm.set_skin_path(&dir)
+            .set("Files", " b.txt | missing.txt || a.txt ");
+        assert_eq!(
+            rm.read_existing_paths("Files", '|'),
+            [dir.join("b.txt"), dir.join("a.txt")]
+        );
+        let logs = rm.logs();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].0, RmLogLevel::LogDebug as i32);
+        assert!(
+            logs[0].1.starts_with("Files: skipping missing path"),
+            "{}",
+            logs[0].1
+        );
+    }
+
+    #[test]
+    fn first_existing_path_is_the_third_candidate() {
+        let dir = temp_dir("first-existing", &["c.txt"]);
+        let rm = MockContext::new();
+        rm.set_skin_path(&dir)
+            .set("Path", "a.txt; missing\\b.txt; c.txt; c.txt");
+        assert_eq!(
+            rm.read_first_existing_path("Path", ';'),
+            Some(dir.join("c.txt"))
+        );
+
+        rm.set("Path", "a.txt; b.txt");
+        assert_eq!(rm.read_first_existing_path("Path", ';'), None);
+    }
+
+    #[test]
+    fn first_existing_path_expands_env_vars() {
+        let dir = temp_dir("env-path", &["music.txt"]);
+        // SAFETY: no other test reads or writes this variable.
+        unsafe { std::env::set_var("RAINMETER_RS_TEST_DIR", &dir) };
+        let rm = MockContext::new();
+        rm.set(
+            "Path",
+            "%RAINMETER_RS_TEST_MISSING%/music.txt; %RAINMETER_RS_TEST_DIR%/music.txt",
+        );
+        assert_eq!(
+            rm.read_first_existing_path("Path", ';'),
+            Some(dir.join("music.txt"))
+        );
+        assert_eq!(
+            expand_env_vars("%RAINMETER_RS_TEST_MISSING%%RAINMETER_RS_TEST_DIR%"),
+            format!("%RAINMETER_RS_TEST_MISSING%{}", dir.display())
+        );
+    }
+
+    #[test]
+    fn resource_paths_come_from_the_resources_variable() {
+        let rm = MockContext::new();
+        assert_eq!(rm.resources_path(), None);
+        assert_eq!(rm.resource_file("Images\\icon.png"), None);
+
+        let resources = std::env::temp_dir()
+            .join("Skins")
+            .join("illustro")
+            .join("@Resources");
+        rm.set_variable("#@#", &resources.to_string_lossy());
+        assert_eq!(rm.resources_path(), Some(resources.clone()));
+        assert_eq!(rm.resource_file("Fonts").unwrap(), resources.join("Fonts"));
+    }
+
+    #[test]
+    fn glob_list_mixes_wildcards_and_plain_paths() {
+        let dir = temp_dir("glob", &["b.png", "a.png", "c.ico", "x.PNG"]);
+        std::fs::create_dir(dir.join("sub.png")).unwrap();
+        let rm = MockContext::new();
+        rm.set_skin_path(&dir)
+            .set("Sources", "c.ico | *.png | missing.ico | ?.ico | */a.png");
+        assert_eq!(
+            rm.read_glob_list("Sources", '|'),
+            [
+                dir.join("c.ico"),
+                dir.join("a.png"),
+                dir.join("b.png"),
+                dir.join("x.PNG"),
+                dir.join("missing.ico"),
+                dir.join("c.ico"),
+            ]
+        );
+        let warnings = rm.warnings();
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(
+            warnings[0].starts_with("Sources: wildcards are only supported in file names"),
+            "{}",
+            warnings[0]
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{RainmeterContext, RmError};
+use windows::Win32::System::Performance::{
+    PDH_FMT_COUNTERVALUE, PDH_FMT_DOUBLE, PDH_HCOUNTER, PDH_HQUERY, PdhAddEnglishCounterW,
+    PdhCloseQuery, PdhCollectQueryData, PdhGetFormattedCounterValue, PdhOpenQueryW,
+};
+use windows::core::{HRESULT, HSTRING, PCWSTR};
+
+/// `PDH_CSTATUS_NEW_DATA`; `0` (`PDH_CSTATUS_VALID_DATA`) and this are the
+/// only statuses that mean the value is usable.
+const PDH_CSTATUS_NEW_DATA: u32 = 1;
+
+fn pdh_result(status: u32) -> Result<(), RmError> {
+    if status == 0 {
+        Ok(())
+    } else {
+        // PDH status codes are laid out like HRESULTs.
+        Err(windows::core::Error::from_hresult(HRESULT(status as i32)).into())
+    }
+}
+
+/// A Windows performance counter, e.g. `\Processor(_Total)\% Processor Time`.
+///
+/// Paths use the English counter names, so skins work on any system
+/// language. Rate counters (most of them, including CPU time) need two
+/// samples before they have a value, so the first [`sample`](Self::sample)
+/// after opening may return `None`.
+///
+/// ```rust,ignore
+/// fn reload(&mut self, rm: RainmeterContext, _max: &mut f64) {
+///     self.counter = rm.read_perf_counter("Counter").ok();
+/// }
+///
+/// fn update(&mut self, _rm: RainmeterContext) -> f64 {
+///     self.counter.as_mut().and_then(|c| c.sample()).unwrap_or(0.0)
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PerfCounter {
+    query: PDH_HQUERY,
+    counter: PDH_HCOUNTER,
+}
+
+// PDH handles aren't tied to the thread that opened them; `&mut self` on
+// `sample` keeps them from being used concurrently.
+unsafe impl Send for PerfCounter {}
+
+impl PerfCounter {
+    /// Open the counter at `path` and take a first sample.
+    pub fn open(path: &str) -> Result<Self, RmError> {
+        let mut query = PDH_HQUERY::default();
+        pdh_result(unsafe { PdhOpenQueryW(PCWSTR::null(), 0, &mut query) })?;
+        // Owning `query` from here on, so it's closed if adding the counter fails.
+        let mut counter = Self {
+            query,
+            counter: PDH_HCOUNTER::default(),
+        };
+        pdh_result(unsafe {
+            PdhAddEnglishCounterW(query, &HSTRING::from(path), 0, &mut counter.counter)
+        })?;
+        pdh_result(unsafe { PdhCollectQueryData(query) })?;
+        Ok(counter)
+    }
+
+    /// Collect a new sample and return the counter's value, or `None` if
+    /// there's no valid value yet (or the counter went away, e.g. a process
+    /// instance that exited).
+    pub fn sample(&mut self) -> Option<f64> {
+        pdh_result(unsafe { PdhCollectQueryData(self.query) }).ok()?;
+        let mut value = PDH_FMT_COUNTERVALUE::default();
+        pdh_result(unsafe {
+            PdhGetFormattedCounterValue(self.counter, PDH_FMT_DOUBLE, None, &mut value)
+        })
+        .ok()?;
+        if value.CStatus > PDH_CSTATUS_NEW_DATA {
+            return None;
+        }
+        Some(unsafe { value.Anonymous.doubleValue })
+    }
+}
+
+impl Drop for PerfCounter {
+    fn drop(&mut self) {
+        // Closing the query also closes its counters.
+        unsafe { PdhCloseQuery(self.query) };
+    }
+}
+
+impl RainmeterContext {
+    /// Open the performance counter named by an option, e.g.
+    /// `Counter=\Processor(_Total)\% Processor Time`. See [`PerfCounter`].
+    pub fn read_perf_counter(&self, key: &str) -> Result<PerfCounter, RmError> {
+        let path = self.read_string(key, "");
+        if path.trim().is_empty() {
+            return Err(RmError::ParseFailed {
+                key: key.to_string(),
+                message: "no counter path given".to_string(),
+            });
+        }
+        PerfCounter::open(path.trim())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+    use std::time::Duration;
+
+    #[test]
+    fn samples_processor_time() {
+        let rm = MockContext::new();
+        rm.set("Counter", r"\Processor(_Total)\% Processor Time");
+        let mut counter = rm.read_perf_counter("Counter").unwrap();
+        for _ in 0..2 {
+            std::thread::sleep(Duration::from_millis(100));
+            let value = counter.sample().unwrap();
+            assert!((0.0..=100.0).contains(&value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_paths() {
+        assert!(PerfCounter::open(r"\No Such Object\Nothing").is_err());
+        let rm = MockContext::new();
+        assert!(matches!(
+            rm.read_perf_counter("Counter"),
+            Err(RmError::ParseFailed { .. })
+        ));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::RmError;
+use std::io::Read;
+use std::os::windows::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::thread::{self, JoinHandle};
+
+/// `CREATE_NO_WINDOW`: don't create a console for console programs, so
+/// nothing flashes on screen when the process starts.
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// What a [`ProcessRunner`] is doing.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ProcessStatus {
+    /// Nothing has been spawned yet, or the last process was terminated.
+    #[default]
+    Idle,
+    Running,
+    /// The process exited. `code` is `None` if it has no exit code; `stdout`
+    /// is its standard output, decoded lossily as UTF-8.
+    Finished {
+        code: Option<i32>,
+        stdout: String,
+    },
+    /// The process couldn't be waited on or its output couldn't be read.
+    Failed(String),
+}
+
+/// Runs one external process at a time without blocking the plugin.
+///
+/// Spawn from `update` or `execute_bang`, then call [`poll`](Self::poll) on
+/// later updates until it reports `Finished`. Output is collected on a
+/// background thread, so a chatty process can't fill its pipe and stall.
+/// The process is killed when the runner is dropped, so keep the runner in
+/// your plugin struct and it's cleaned up when `finalize` drops the plugin.
+#[derive(Debug, Default)]
+pub struct ProcessRunner {
+    child: Option<Child>,
+    reader: Option<JoinHandle<std::io::Result<Vec<u8>>>>,
+    status: ProcessStatus,
+}
+
+impl ProcessRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start `command_line` (e.g. a rendered
+    /// [`CommandTemplate`](crate::CommandTemplate)), terminating any process
+    /// this runner already has. The first token (quoted or not) is the
+    /// program; the rest is passed through verbatim as its arguments.
+    pub fn spawn(&mut self, command_line: &str) -> Result<(), RmError> {
+        self.terminate();
+        let (program, args) = split_program(command_line.trim());
+        let mut child = Command::new(program)
+            .raw_arg(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .creation_flags(CREATE_NO_WINDOW)
+            .spawn()?;
+        let mut stdout = child.stdout.take();
+        self.reader = Some(thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(stdout) = stdout.as_mut() {
+                stdout.read_to_end(&mut buf)?;
+            }
+            Ok(buf)
+        }));
+        self.child = Some(child);
+        self.status = ProcessStatus::Running;
+        Ok(())
+    }
+
+    /// Check on the process without blocking and return the current status.
+    pub fn poll(&mut self) -> &ProcessStatus {
+        let Some(child) = self.child.as_mut() else {
+            return &self.status;
+        };
+        let exit = match child.try_wait() {
+            Ok(Some(exit)) => exit,
+            Ok(None) => return &self.status,
+            Err(err) => {
+                self.status = ProcessStatus::Failed(err.to_string());
+                self.child = None;
+                self.reader = None;
+                return &self.status;
+            }
+        };
+        // A child of the process may still hold the pipe open; wait for the
+        // reader to finish on a later poll rather than blocking here.
+        if !self.reader.as_ref().is_none_or(|r| r.is_finished()) {
+            return &self.status;
+        }
+        let stdout = match self.reader.take().map(JoinHandle::join) {
+            Some(Ok(Ok(buf))) => buf,
+            Some(Ok(Err(err))) => {
+                self.status = ProcessStatus::Failed(err.to_string());
+                self.child = None;
+                return &self.status;
+            }
+            Some(Err(_)) | None => Vec::new(),
+        };
+        self.child = None;
+        self.status = ProcessStatus::Finished {
+            code: exit.code(),
+            stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        };
+        &self.status
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status == ProcessStatus::Running
+    }
+
+    /// Kill the process if it's still running and go back to `Idle`.
+    pub fn terminate(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        // The reader ends by itself once the pipe closes; don't wait for it.
+        self.reader = None;
+        self.status = ProcessStatus::Idle;
+    }
+}
+
+impl Drop for ProcessRunner {
+    fn drop(&mut self) {
+        self.terminate();
+    }
+}
+
+/// Split a command line into the program and the (unparsed) rest.
+fn split_program(command_line: &str) -> (&str, &str) {
+    if let Some(quoted) = command_line.strip_prefix('"') {
+        return match quoted.split_once('"') {
+            Some((program, rest)) => (program, rest.trim_start()),
+            None => (quoted, ""),
+        };
+    }
+    match command_line.split_once([' ', '\t']) {
+        Some((program, rest)) => (program, rest.trim_start()),
+        None => (command_line, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn splits_off_the_program() {
+        assert_eq!(split_program("cmd /c echo hi"), ("cmd", "/c echo hi"));
+        assert_eq!(
+            split_program(r#""C:\Program Files\app.exe"  --x "a b""#),
+            (r"C:\Program Files\app.exe", r#"--x "a b""#)
+        );
+        assert_eq!(split_program(r#""unterminated"#), ("unterminated", ""));
+        assert_eq!(split_program("app.exe"), ("app.exe", ""));
+    }
+
+    #[test]
+    fn collects_output_without_blocking() {
+        let mut runner = ProcessRunner::new();
+        assert_eq!(runner.poll(), &ProcessStatus::Idle);
+        runner.spawn("cmd /c echo hi").unwrap();
+        assert!(runner.is_running());
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while runner.poll() == &ProcessStatus::Running {
+            assert!(Instant::now() < deadline, "timed out");
+            thread::sleep(Duration::from_millis(10));
+        }
+        match runner.poll() {
+            ProcessStatus::Finished { code, stdout } => {
+                assert_eq!(*code, Some(0));
+                assert_eq!(stdout.trim_end(), "hi");
+            }
+            other => panic!("{:?}", other),
+        }
+        assert!(!runner.is_running());
+    }
+}
@@ -0,0 +1,89 @@
+use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW, TH32CS_SNAPPROCESS,
+};
+use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+use windows::Win32::System::Threading::GetCurrentProcess;
+
+/// A running process, as reported by [`list_processes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    /// Executable file name without a path, e.g. `Rainmeter.exe`.
+    pub exe_name: String,
+}
+
+/// Closes the Toolhelp snapshot when dropped.
+struct Snapshot(HANDLE);
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        let _ = unsafe { CloseHandle(self.0) };
+    }
+}
+
+/// Snapshot of the processes currently running on the system.
+///
+/// Returns an empty list if the snapshot can't be taken. This walks every
+/// process, so call it from a worker thread or at a modest update rate rather
+/// than on every `update`.
+pub fn list_processes() -> Vec<ProcessInfo> {
+    let mut processes = Vec::new();
+    let Ok(handle) = (unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }) else {
+        return processes;
+    };
+    let snapshot = Snapshot(handle);
+
+    let mut entry = PROCESSENTRY32W {
+        dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+        ..Default::default()
+    };
+    let mut next = unsafe { Process32FirstW(snapshot.0, &mut entry) };
+    while next.is_ok() {
+        let len = entry
+            .szExeFile
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(entry.szExeFile.len());
+        processes.push(ProcessInfo {
+            pid: entry.th32ProcessID,
+            exe_name: String::from_utf16_lossy(&entry.szExeFile[..len]),
+        });
+        next = unsafe { Process32NextW(snapshot.0, &mut entry) };
+    }
+    processes
+}
+
+/// Working set of the current process in bytes, or `0` if it can't be read.
+///
+/// Plugins run inside Rainmeter, so this covers Rainmeter and every loaded
+/// skin and plugin, not just yours. It's still useful for spotting a leak:
+/// log it periodically and watch for steady growth.
+pub fn current_memory_usage() -> u64 {
+    let mut counters = PROCESS_MEMORY_COUNTERS {
+        cb: std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+        ..Default::default()
+    };
+    match unsafe { GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, counters.cb) } {
+        Ok(()) => counters.WorkingSetSize as u64,
+        Err(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_the_current_process() {
+        let pid = std::process::id();
+        let processes = list_processes();
+        let current = processes.iter().find(|process| process.pid == pid);
+        assert!(current.is_some_and(|process| !process.exe_name.is_empty()));
+    }
+
+    #[test]
+    fn reads_the_working_set() {
+        assert!(current_memory_usage() > 0);
+    }
+}
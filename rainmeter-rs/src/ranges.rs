@@ -0,0 +1,109 @@
+//! Integer range lists such as `Ports=80,443,8000-8010`.
+
+use crate::{RainmeterContext, RmLogLevel};
+use std::ops::RangeInclusive;
+
+/// Most values [`RainmeterContext::read_int_list_expanded`] produces, so a
+/// typo like `1-1000000000` can't exhaust memory.
+pub const MAX_EXPANDED_VALUES: usize = 10_000;
+
+/// Parse a single `a` or `a-b` item. Negative bounds are allowed
+/// (`-5`, `-10--5`); reversed ranges are rejected.
+pub(crate) fn parse_int_range(item: &str) -> Option<RangeInclusive<i64>> {
+    let item = item.trim();
+    // Skip a leading sign so `-5` isn't mistaken for a range separator.
+    let split = item
+        .char_indices()
+        .skip(1)
+        .find(|&(_, c)| c == '-')
+        .map(|(i, _)| i);
+    let (start, end) = match split {
+        Some(i) => (item[..i].trim(), item[i + 1..].trim()),
+        None => (item, item),
+    };
+    let (start, end) = (start.parse::<i64>().ok()?, end.parse::<i64>().ok()?);
+    (start <= end).then_some(start..=end)
+}
+
+/// Returns `true` if `value` falls in any of `ranges`.
+pub fn contains(ranges: &[RangeInclusive<i64>], value: i64) -> bool {
+    ranges.iter().any(|range| range.contains(&value))
+}
+
+impl RainmeterContext {
+    /// Read a comma-separated list of integers and inclusive `a-b` ranges.
+    /// Invalid items are logged and skipped. Use [`contains`] to test values.
+    pub fn read_int_ranges(&self, key: &str) -> Vec<RangeInclusive<i64>> {
+        self.read_list_schema(key, ',', parse_int_range)
+    }
+
+    /// Like [`read_int_ranges`](Self::read_int_ranges), but expands the
+    /// ranges into their values in order, so `1,3,5-8,10` gives
+    /// `[1, 3, 5, 6, 7, 8, 10]`. Duplicates are kept.
+    ///
+    /// At most [`MAX_EXPANDED_VALUES`] values are returned; longer lists are
+    /// cut off with a warning.
+    pub fn read_int_list_expanded(&self, key: &str) -> Vec<i64> {
+        let mut values = Vec::new();
+        for range in self.read_int_ranges(key) {
+            let mut items = range.into_iter();
+            values.extend(items.by_ref().take(MAX_EXPANDED_VALUES - values.len()));
+            if items.next().is_some() {
+                self.log(
+                    RmLogLevel::LogWarning,
+                    &format!(
+                        "{}: list expands to more than {} values, ignoring the rest",
+                        key, MAX_EXPANDED_VALUES
+                    ),
+                );
+                break;
+            }
+        }
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn parses_singles_and_ranges() {
+        assert_eq!(parse_int_range("80"), Some(80..=80));
+        assert_eq!(parse_int_range(" 8000 - 8010 "), Some(8000..=8010));
+        assert_eq!(parse_int_range("-5"), Some(-5..=-5));
+        assert_eq!(parse_int_range("-10--5"), Some(-10..=-5));
+        assert_eq!(parse_int_range("10-5"), None);
+        assert_eq!(parse_int_range("http"), None);
+        assert_eq!(parse_int_range("1-"), None);
+    }
+
+    #[test]
+    fn reads_mixed_list_skipping_invalid_items() {
+        let rm = MockContext::new();
+        rm.set("Ports", "80, 443,8000-8010,abc,20-10");
+        let ranges = rm.read_int_ranges("Ports");
+        assert_eq!(ranges, vec![80..=80, 443..=443, 8000..=8010]);
+        assert_eq!(rm.warnings().len(), 2);
+
+        assert!(contains(&ranges, 443));
+        assert!(contains(&ranges, 8005));
+        assert!(!contains(&ranges, 8011));
+        assert!(!contains(&[], 80));
+    }
+
+    #[test]
+    fn expanded_lists_are_capped() {
+        let rm = MockContext::new();
+        rm.set("Items", "1,3,5-8,10");
+        assert_eq!(rm.read_int_list_expanded("Items"), [1, 3, 5, 6, 7, 8, 10]);
+
+        rm.set("Items", "1-1000000000");
+        assert_eq!(
+            rm.read_int_list_expanded("Items").len(),
+            MAX_EXPANDED_VALUES
+        );
+        assert_eq!(rm.warnings().len(), 1);
+    }
+}
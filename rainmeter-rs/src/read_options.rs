@@ -0,0 +1,151 @@
+use crate::RainmeterContext;
+
+/// Types that can be read from a measure option; used by [`read_options!`].
+pub trait ReadOption: Sized {
+    fn read_option(rm: &RainmeterContext, key: &str, default: Self) -> Self;
+}
+
+/// Converts a [`read_options!`] default into the option's type. Unlike
+/// `Into`, this keeps integer literals inferable (`count: u32 = 5`) while
+/// still accepting `&str` defaults for `String` options.
+#[doc(hidden)]
+pub trait OptionDefault<T> {
+    fn into_option_default(self) -> T;
+}
+
+impl<T> OptionDefault<T> for T {
+    fn into_option_default(self) -> T {
+        self
+    }
+}
+
+impl OptionDefault<String> for &str {
+    fn into_option_default(self) -> String {
+        self.to_string()
+    }
+}
+
+impl ReadOption for String {
+    fn read_option(rm: &RainmeterContext, key: &str, default: Self) -> Self {
+        rm.read_string(key, &default)
+    }
+}
+
+impl ReadOption for bool {
+    fn read_option(rm: &RainmeterContext, key: &str, default: Self) -> Self {
+        rm.read_bool(key, default)
+    }
+}
+
+impl ReadOption for f64 {
+    fn read_option(rm: &RainmeterContext, key: &str, default: Self) -> Self {
+        rm.read_formula(key, default)
+    }
+}
+
+impl ReadOption for f32 {
+    fn read_option(rm: &RainmeterContext, key: &str, default: Self) -> Self {
+        rm.read_formula(key, default as f64) as f32
+    }
+}
+
+macro_rules! impl_read_option_int {
+    ($($t:ty),*) => {
+        $(
+            impl ReadOption for $t {
+                /// Reads the option as a formula and truncates (saturating) to the integer type.
+                fn read_option(rm: &RainmeterContext, key: &str, default: Self) -> Self {
+                    rm.read_formula(key, default as f64) as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_read_option_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// Read several options at once into a tuple, in declaration order.
+///
+/// Each entry is `name: Type = default`; the option key is the name as
+/// written (Rainmeter option names are case-insensitive). The type must
+/// implement [`ReadOption`], and `default` may be a `&str` for `String` options.
+///
+/// ```rust,no_run
+/// # use rainmeter::{RainmeterContext, read_options};
+/// # let rm = RainmeterContext::new(std::ptr::null_mut());
+/// let (interval, host, enabled) = read_options!(rm, {
+///     interval: u32 = 5,
+///     host: String = "localhost",
+///     enabled: bool = false,
+/// });
+/// ```
+#[macro_export]
+macro_rules! read_options {
+    ($rm:expr, { $($name:ident : $ty:ty = $default:expr),* $(,)? }) => {{
+        let rm: &$crate::RainmeterContext = &$rm;
+        ($(
+            <$ty as $crate::ReadOption>::read_option(
+                rm,
+                stringify!($name),
+                $crate::OptionDefault::<$ty>::into_option_default($default),
+            ),
+        )*)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::MockContext;
+
+    #[test]
+    fn reads_every_option_type() {
+        let rm = MockContext::new();
+        rm.set("host", "example.com")
+            .set("enabled", "1")
+            .set("ratio", "0.5")
+            .set("scale", "1.5")
+            .set("small", "-3")
+            .set("port", "8080")
+            .set("wrap", "300");
+
+        let (host, enabled, ratio, scale, small, port, wrap) = read_options!(rm, {
+            host: String = "localhost",
+            enabled: bool = false,
+            ratio: f64 = 1.0,
+            scale: f32 = 1.0,
+            small: i8 = 0,
+            port: u16 = 80,
+            wrap: u8 = 0,
+        });
+        assert_eq!(host, "example.com");
+        assert!(enabled);
+        assert_eq!(ratio, 0.5);
+        assert_eq!(scale, 1.5);
+        assert_eq!(small, -3);
+        assert_eq!(port, 8080);
+        // Out-of-range values saturate.
+        assert_eq!(wrap, 255);
+
+        let (a, b, c, d, e, f, g) = read_options!(rm, {
+            a: i16 = 1,
+            b: i32 = 2,
+            c: i64 = 3,
+            d: isize = 4,
+            e: u32 = 5,
+            f: u64 = 6,
+            g: usize = 7,
+        });
+        assert_eq!((a, b, c, d, e, f, g), (1, 2, 3, 4, 5, 6, 7));
+    }
+
+    #[test]
+    fn missing_options_use_their_defaults() {
+        let rm = MockContext::new();
+        let (interval, host, enabled) = read_options!(rm, {
+            interval: u32 = 5,
+            host: String = String::from("localhost"),
+            enabled: bool = true,
+        });
+        assert_eq!((interval, host.as_str(), enabled), (5, "localhost", true));
+    }
+}
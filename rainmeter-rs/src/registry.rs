@@ -0,0 +1,230 @@
+use crate::{RainmeterContext, RmError};
+use windows::Win32::Foundation::{ERROR_MORE_DATA, ERROR_UNSUPPORTED_TYPE};
+use windows::Win32::System::Registry::{
+    HKEY, HKEY_CLASSES_ROOT, HKEY_CURRENT_CONFIG, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE,
+    HKEY_USERS, REG_DWORD, REG_EXPAND_SZ, REG_QWORD, REG_SZ, REG_VALUE_TYPE, RRF_RT_REG_DWORD,
+    RRF_RT_REG_QWORD, RRF_RT_REG_SZ, RegGetValueW,
+};
+use windows::core::HSTRING;
+
+/// Split `HKLM\Software\Vendor\Value` into `(hive, subkey, value name)`.
+/// A trailing `\` selects the key's default value.
+fn parse_registry_path(path: &str) -> Option<(HKEY, &str, &str)> {
+    let path = path.trim();
+    let (hive, rest) = path.split_once('\\')?;
+    let hive = match hive.to_ascii_uppercase().as_str() {
+        "HKLM" | "HKEY_LOCAL_MACHINE" => HKEY_LOCAL_MACHINE,
+        "HKCU" | "HKEY_CURRENT_USER" => HKEY_CURRENT_USER,
+        "HKCR" | "HKEY_CLASSES_ROOT" => HKEY_CLASSES_ROOT,
+        "HKU" | "HKEY_USERS" => HKEY_USERS,
+        "HKCC" | "HKEY_CURRENT_CONFIG" => HKEY_CURRENT_CONFIG,
+        _ => return None,
+    };
+    let (subkey, value) = rest.rsplit_once('\\').unwrap_or(("", rest));
+    Some((hive, subkey, value))
+}
+
+/// Read a `REG_SZ`, `REG_EXPAND_SZ` (expanded), `REG_DWORD` or `REG_QWORD`
+/// value and format it as a string.
+pub(crate) fn read_registry_string(
+    hive: HKEY,
+    subkey: &str,
+    value: &str,
+) -> Result<String, RmError> {
+    let unsupported = |message: String| RmError::ParseFailed {
+        key: format!("{}\\{}", subkey, value),
+        message,
+    };
+    let (subkey, value) = (HSTRING::from(subkey), HSTRING::from(value));
+    // RRF_RT_REG_SZ also accepts REG_EXPAND_SZ values and expands them;
+    // asking for RRF_RT_REG_EXPAND_SZ as well is only valid with RRF_NOEXPAND.
+    let flags = RRF_RT_REG_SZ | RRF_RT_REG_DWORD | RRF_RT_REG_QWORD;
+    let mut kind = REG_VALUE_TYPE::default();
+    let mut size = 0u32;
+    let status = unsafe { RegGetValueW(hive, &subkey, &value, flags, None, None, Some(&mut size)) };
+    if status == ERROR_UNSUPPORTED_TYPE {
+        return Err(unsupported(
+            "registry value is not a string or number".to_string(),
+        ));
+    }
+    status.ok()?;
+    let mut data: Vec<u8> = Vec::new();
+    loop {
+        data.resize(size as usize, 0);
+        let status = unsafe {
+            RegGetValueW(
+                hive,
+                &subkey,
+                &value,
+                flags,
+                Some(&mut kind),
+                Some(data.as_mut_ptr().cast()),
+                Some(&mut size),
+            )
+        };
+        // The value grew since the size query; `size` now holds the new size.
+        if status == ERROR_MORE_DATA {
+            continue;
+        }
+        status.ok()?;
+        data.truncate(size as usize);
+        break;
+    }
+
+    Ok(match kind {
+        REG_DWORD if data.len() >= 4 => {
+            u32::from_le_bytes([data[0], data[1], data[2], data[3]]).to_string()
+        }
+        REG_QWORD if data.len() >= 8 => {
+            u64::from_le_bytes(data[..8].try_into().unwrap()).to_string()
+        }
+        REG_SZ | REG_EXPAND_SZ => {
+            let wide: Vec<u16> = data
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .take_while(|&unit| unit != 0)
+                .collect();
+            String::from_utf16_lossy(&wide)
+        }
+        _ => {
+            return Err(unsupported(format!(
+                "unsupported registry value type {}",
+                kind.0
+            )));
+        }
+    })
+}
+
+impl RainmeterContext {
+    /// Read an option naming a registry value, like
+    /// `RegSource=HKLM\SOFTWARE\Microsoft\Windows NT\CurrentVersion\ProductName`,
+    /// and return that value as a string.
+    ///
+    /// The hive may be abbreviated (`HKLM`, `HKCU`, `HKCR`, `HKU`, `HKCC`) or
+    /// spelled out. The last path component is the value name; a trailing `\`
+    /// reads the key's default value. `REG_SZ`, `REG_EXPAND_SZ` (expanded),
+    /// `REG_DWORD` and `REG_QWORD` values are supported.
+    pub fn read_registry_value(&self, key: &str) -> Result<String, RmError> {
+        let path = self.read_string(key, "");
+        let (hive, subkey, value) =
+            parse_registry_path(&path).ok_or_else(|| RmError::ParseFailed {
+                key: key.to_string(),
+                message: format!("'{}' is not a registry path like HKCU\\Key\\Value", path),
+            })?;
+        read_registry_string(hive, subkey, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+    use windows::Win32::System::Registry::{REG_BINARY, RegDeleteTreeW, RegSetKeyValueW};
+
+    /// A scratch key under `HKCU\Software`, deleted on drop.
+    struct TestKey(String);
+
+    impl TestKey {
+        fn new(name: &str) -> Self {
+            Self(format!(
+                r"Software\rainmeter-rs-tests\{}-{}",
+                name,
+                std::process::id()
+            ))
+        }
+
+        fn set(&self, value: &str, kind: REG_VALUE_TYPE, data: &[u8]) {
+            let status = unsafe {
+                RegSetKeyValueW(
+                    HKEY_CURRENT_USER,
+                    &HSTRING::from(self.0.as_str()),
+                    &HSTRING::from(value),
+                    kind.0,
+                    Some(data.as_ptr().cast()),
+                    data.len() as u32,
+                )
+            };
+            status.ok().unwrap();
+        }
+
+        fn set_string(&self, value: &str, kind: REG_VALUE_TYPE, text: &str) {
+            let data: Vec<u8> = text
+                .encode_utf16()
+                .chain(Some(0))
+                .flat_map(u16::to_le_bytes)
+                .collect();
+            self.set(value, kind, &data);
+        }
+
+        fn read(&self, value: &str) -> Result<String, RmError> {
+            read_registry_string(HKEY_CURRENT_USER, &self.0, value)
+        }
+    }
+
+    impl Drop for TestKey {
+        fn drop(&mut self) {
+            let _ = unsafe { RegDeleteTreeW(HKEY_CURRENT_USER, &HSTRING::from(self.0.as_str())) };
+        }
+    }
+
+    #[test]
+    fn parses_registry_paths() {
+        let (hive, subkey, value) = parse_registry_path(r"HKLM\Software\Vendor\Name").unwrap();
+        assert_eq!(hive, HKEY_LOCAL_MACHINE);
+        assert_eq!((subkey, value), (r"Software\Vendor", "Name"));
+
+        let (hive, subkey, value) = parse_registry_path(r"hkey_current_user\Software\").unwrap();
+        assert_eq!(hive, HKEY_CURRENT_USER);
+        assert_eq!((subkey, value), ("Software", ""));
+
+        assert!(parse_registry_path(r"HKXX\Software\Name").is_none());
+        assert!(parse_registry_path("HKLM").is_none());
+    }
+
+    #[test]
+    fn reads_each_supported_type() {
+        let key = TestKey::new("types");
+        key.set_string("Text", REG_SZ, "hello");
+        key.set_string("Expand", REG_EXPAND_SZ, "%USERNAME%");
+        key.set("Dword", REG_DWORD, &42u32.to_le_bytes());
+        key.set("Qword", REG_QWORD, &(1u64 << 40).to_le_bytes());
+
+        assert_eq!(key.read("Text").unwrap(), "hello");
+        let expanded = key.read("Expand").unwrap();
+        assert!(
+            !expanded.is_empty() && !expanded.contains('%'),
+            "{}",
+            expanded
+        );
+        assert_eq!(key.read("Dword").unwrap(), "42");
+        assert_eq!(key.read("Qword").unwrap(), "1099511627776");
+    }
+
+    #[test]
+    fn unsupported_types_name_the_value() {
+        let key = TestKey::new("binary");
+        key.set("Blob", REG_BINARY, &[1, 2, 3]);
+        match key.read("Blob") {
+            Err(RmError::ParseFailed { key: path, .. }) => {
+                assert_eq!(path, format!("{}\\Blob", key.0))
+            }
+            other => panic!("expected ParseFailed, got {:?}", other),
+        }
+        assert!(matches!(key.read("Missing"), Err(RmError::Win32(_))));
+    }
+
+    #[test]
+    fn reads_value_named_by_option() {
+        let key = TestKey::new("option");
+        key.set_string("Name", REG_SZ, "Rainmeter");
+        let rm = MockContext::new();
+        rm.set("RegSource", &format!(r"HKCU\{}\Name", key.0));
+        assert_eq!(rm.read_registry_value("RegSource").unwrap(), "Rainmeter");
+
+        rm.set("RegSource", "nowhere");
+        match rm.read_registry_value("RegSource") {
+            Err(RmError::ParseFailed { key, .. }) => assert_eq!(key, "RegSource"),
+            other => panic!("expected ParseFailed, got {:?}", other),
+        }
+    }
+}
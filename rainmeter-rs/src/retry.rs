@@ -0,0 +1,192 @@
+use crate::RainmeterContext;
+use std::time::{Duration, Instant};
+
+/// Retries a failing operation a limited number of times with exponential
+/// backoff, and describes its state for display, e.g. `Retrying (2/5)`.
+///
+/// ```rust,ignore
+/// fn update(&mut self, _rm: RainmeterContext) -> f64 {
+///     let now = Instant::now();
+///     if self.retry.ready(now) {
+///         match self.fetch() {
+///             Ok(value) => { self.value = value; self.retry.succeed(); }
+///             Err(_) => self.retry.fail(now),
+///         }
+///     }
+///     self.value
+/// }
+///
+/// fn get_string(&mut self, _rm: RainmeterContext) -> Option<String> {
+///     (!self.retry.is_idle()).then(|| self.retry.status_string())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    /// Failures since the last success.
+    failures: u32,
+    next_attempt: Option<Instant>,
+}
+
+impl RetryPolicy {
+    /// Allow `max_attempts` retries after a failure, waiting `base_delay`
+    /// before the first and doubling the wait each time after that.
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            base_delay,
+            failures: 0,
+            next_attempt: None,
+        }
+    }
+
+    /// A policy using the measure's `MaxRetries=` (default `max_attempts`)
+    /// and `RetryDelay=` (a duration like `5s`, default `base_delay`)
+    /// options.
+    pub fn from_options(rm: &RainmeterContext, max_attempts: u32, base_delay: Duration) -> Self {
+        let max_attempts = rm.read_clamped_int("MaxRetries", max_attempts as i32, 0, 100);
+        Self::new(
+            max_attempts as u32,
+            rm.read_duration("RetryDelay", base_delay),
+        )
+    }
+
+    /// Whether the operation should be tried now: always while nothing has
+    /// failed, once the backoff has elapsed while retrying, and never after
+    /// all retries are used up (until [`reset`](Self::reset) or
+    /// [`succeed`](Self::succeed)).
+    pub fn ready(&self, now: Instant) -> bool {
+        !self.gave_up() && self.next_attempt.is_none_or(|next| now >= next)
+    }
+
+    /// Record a failed attempt and schedule the next retry.
+    pub fn fail(&mut self, now: Instant) {
+        self.failures = self.failures.saturating_add(1);
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << (self.failures - 1).min(16));
+        self.next_attempt = Some(now + backoff);
+    }
+
+    /// Record a successful attempt, clearing the failure state.
+    pub fn succeed(&mut self) {
+        self.failures = 0;
+        self.next_attempt = None;
+    }
+
+    /// Start over as if nothing had failed, e.g. after the user asks to
+    /// retry from a bang.
+    pub fn reset(&mut self) {
+        self.succeed();
+    }
+
+    /// Whether the last attempt succeeded (or none has been made).
+    pub fn is_idle(&self) -> bool {
+        self.failures == 0
+    }
+
+    /// Whether all retries failed.
+    pub fn gave_up(&self) -> bool {
+        self.failures > self.max_attempts
+    }
+
+    /// The retry in progress, counting from 1, or `0` if nothing failed.
+    pub fn attempt(&self) -> u32 {
+        self.failures.min(self.max_attempts)
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// The state for display: an empty string while things work,
+    /// `Retrying (2/5)` while retrying and `Failed after 5 retries` once the
+    /// retries are used up.
+    pub fn status_string(&self) -> String {
+        if self.is_idle() {
+            String::new()
+        } else if self.gave_up() {
+            let noun = if self.max_attempts == 1 {
+                "retry"
+            } else {
+                "retries"
+            };
+            format!("Failed after {} {}", self.max_attempts, noun)
+        } else {
+            format!("Retrying ({}/{})", self.attempt(), self.max_attempts)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn status_steps_through_the_retries() {
+        let mut retry = RetryPolicy::new(3, SECOND);
+        let now = Instant::now();
+        let mut statuses = vec![retry.status_string()];
+        for _ in 0..4 {
+            retry.fail(now);
+            statuses.push(retry.status_string());
+        }
+        assert_eq!(
+            statuses,
+            [
+                "",
+                "Retrying (1/3)",
+                "Retrying (2/3)",
+                "Retrying (3/3)",
+                "Failed after 3 retries",
+            ]
+        );
+        assert!(retry.gave_up());
+        assert!(!retry.ready(now + Duration::from_secs(3600)));
+
+        retry.reset();
+        assert!(retry.is_idle());
+        assert_eq!(retry.status_string(), "");
+    }
+
+    #[test]
+    fn backoff_doubles() {
+        let mut retry = RetryPolicy::new(5, SECOND);
+        let mut now = Instant::now();
+        assert!(retry.ready(now));
+        for wait in [1, 2, 4, 8] {
+            retry.fail(now);
+            let next = now + wait * SECOND;
+            assert!(!retry.ready(next - Duration::from_millis(1)), "{}s", wait);
+            assert!(retry.ready(next), "{}s", wait);
+            now = next;
+        }
+        retry.succeed();
+        assert!(retry.ready(now));
+    }
+
+    #[test]
+    fn a_single_retry_is_singular() {
+        let mut retry = RetryPolicy::new(1, SECOND);
+        let now = Instant::now();
+        retry.fail(now);
+        assert_eq!(retry.status_string(), "Retrying (1/1)");
+        retry.fail(now);
+        assert_eq!(retry.status_string(), "Failed after 1 retry");
+    }
+
+    #[test]
+    fn reads_retry_options() {
+        let rm = MockContext::new();
+        rm.set("MaxRetries", "7").set("RetryDelay", "500ms");
+        let mut retry = RetryPolicy::from_options(&rm.context(), 3, SECOND);
+        assert_eq!(retry.max_attempts(), 7);
+        let now = Instant::now();
+        retry.fail(now);
+        assert!(retry.ready(now + Duration::from_millis(500)));
+    }
+}
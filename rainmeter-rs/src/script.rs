@@ -0,0 +1,220 @@
+//! Small user scripts evaluated by the plugin, behind the `lua` feature.
+
+use crate::{RainmeterContext, RmError, RmLogLevel};
+use mlua::{Function, HookTriggers, Lua, LuaOptions, StdLib, Table, VmState};
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Instructions between checks of the instruction budget.
+const HOOK_INTERVAL: u32 = 10_000;
+/// Hook calls (so `HOOK_INTERVAL` instructions each) allowed per evaluation.
+const MAX_HOOK_CALLS: u32 = 100;
+/// Memory a script's Lua state may use.
+const MEMORY_LIMIT: usize = 4 * 1024 * 1024;
+
+fn script_error(err: mlua::Error) -> RmError {
+    RmError::Script(err.to_string())
+}
+
+/// A compiled script, ready to be evaluated with different variables.
+///
+/// The script is either an expression (`x * 2 + y`) or a chunk that
+/// `return`s a number (`if x > y then return x end return y`).
+///
+/// # Sandbox
+///
+/// Scripts are Lua 5.4 with only the `math`, `string` and `table`
+/// libraries loaded, and without the base library's loaders (`dofile`,
+/// `loadfile`, `load`, `require`) or `collectgarbage`. Each script gets its own Lua state with a
+/// memory limit, and an evaluation is stopped after about a million
+/// instructions.
+///
+/// Every evaluation starts with fresh globals: variables a script assigns
+/// (and the `vars` of earlier calls) are gone by the next
+/// [`eval`](Self::eval). Only changes to the library tables themselves
+/// (`math.pi = 3`) carry over. Scripts can't reach the file system, processes or the
+/// network, but they are still code: treat them as skin configuration and
+/// never evaluate scripts built from downloaded data.
+///
+/// ```rust
+/// # use rainmeter::CompiledScript;
+/// let script = CompiledScript::compile("math.max(a, b) * 2").unwrap();
+/// assert_eq!(script.eval(&[("a", 3.0), ("b", 5.0)]).unwrap(), 10.0);
+/// ```
+pub struct CompiledScript {
+    source: String,
+    lua: Lua,
+    function: Function,
+    /// Metatable for each evaluation's environment, falling back to the
+    /// sandboxed globals for the libraries.
+    env_meta: Table,
+    hook_calls: Arc<AtomicU32>,
+}
+
+impl CompiledScript {
+    /// Compile `source` in a fresh sandboxed Lua state.
+    pub fn compile(source: &str) -> Result<Self, RmError> {
+        let lua = Lua::new_with(
+            StdLib::MATH | StdLib::STRING | StdLib::TABLE,
+            LuaOptions::default(),
+        )
+        .map_err(script_error)?;
+        let globals = lua.globals();
+        for name in ["dofile", "loadfile", "load", "require", "collectgarbage"] {
+            globals.raw_remove(name).map_err(script_error)?;
+        }
+        lua.set_memory_limit(MEMORY_LIMIT).map_err(script_error)?;
+        let env_meta = lua.create_table().map_err(script_error)?;
+        env_meta.raw_set("__index", globals).map_err(script_error)?;
+
+        let hook_calls = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&hook_calls);
+        lua.set_hook(
+            HookTriggers::new().every_nth_instruction(HOOK_INTERVAL),
+            move |_, _| {
+                if counter.fetch_add(1, Ordering::Relaxed) >= MAX_HOOK_CALLS {
+                    Err(mlua::Error::runtime("script ran for too long"))
+                } else {
+                    Ok(VmState::Continue)
+                }
+            },
+        );
+
+        // Like the Lua REPL: try the source as an expression first.
+        let function = lua
+            .load(format!("return {}", source))
+            .into_function()
+            .or_else(|_| lua.load(source).into_function())
+            .map_err(script_error)?;
+        Ok(Self {
+            source: source.to_string(),
+            lua,
+            function,
+            env_meta,
+            hook_calls,
+        })
+    }
+
+    /// The script's source.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Run the script with `vars` set as global variables and return its
+    /// result, which must be a number (or a string Lua can convert to one).
+    pub fn eval(&self, vars: &[(&str, f64)]) -> Result<f64, RmError> {
+        let env = self.lua.create_table().map_err(script_error)?;
+        env.set_metatable(Some(self.env_meta.clone()));
+        // Otherwise `_G.x = 1` would reach the shared globals.
+        env.raw_set("_G", &env).map_err(script_error)?;
+        for (name, value) in vars {
+            env.raw_set(*name, *value).map_err(script_error)?;
+        }
+        self.function.set_environment(env).map_err(script_error)?;
+        self.hook_calls.store(0, Ordering::Relaxed);
+        self.function.call::<f64>(()).map_err(script_error)
+    }
+}
+
+impl fmt::Debug for CompiledScript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompiledScript")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RainmeterContext {
+    /// Read and compile a script option, e.g. `Script=(used / total) * 100`.
+    /// See [`CompiledScript`] for the syntax and sandbox. Returns `None` if the
+    /// option is missing or doesn't compile; the latter is logged.
+    ///
+    /// Compile once in `reload` and [`eval`](CompiledScript::eval) in
+    /// `update`:
+    ///
+    /// ```rust,ignore
+    /// fn update(&mut self, rm: RainmeterContext) -> f64 {
+    ///     let Some(script) = &self.script else { return 0.0 };
+    ///     script.eval(&[("used", self.used), ("total", self.total)]).unwrap_or_else(|err| {
+    ///         rm.log(RmLogLevel::LogError, &err.to_string());
+    ///         0.0
+    ///     })
+    /// }
+    /// ```
+    pub fn read_script(&self, key: &str) -> Option<CompiledScript> {
+        let source = self.read_string(key, "");
+        if source.trim().is_empty() {
+            return None;
+        }
+        match CompiledScript::compile(&source) {
+            Ok(script) => Some(script),
+            Err(err) => {
+                self.log(RmLogLevel::LogWarning, &format!("{}: {}", key, err));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn evaluates_expressions_and_chunks() {
+        let script = CompiledScript::compile("(used / total) * 100").unwrap();
+        assert_eq!(script.eval(&[("used", 1.0), ("total", 4.0)]).unwrap(), 25.0);
+
+        let script = CompiledScript::compile("if x > y then return x end return y").unwrap();
+        assert_eq!(script.eval(&[("x", 2.0), ("y", 7.0)]).unwrap(), 7.0);
+        assert_eq!(script.eval(&[("x", 9.0), ("y", 7.0)]).unwrap(), 9.0);
+    }
+
+    #[test]
+    fn globals_do_not_persist_between_evaluations() {
+        let script = CompiledScript::compile("count = (count or 0) + 1 return count").unwrap();
+        assert_eq!(script.eval(&[]).unwrap(), 1.0);
+        assert_eq!(script.eval(&[]).unwrap(), 1.0);
+
+        let script =
+            CompiledScript::compile("_G.total = (_G.total or 0) + 1 return total").unwrap();
+        assert_eq!(script.eval(&[]).unwrap(), 1.0);
+        assert_eq!(script.eval(&[]).unwrap(), 1.0);
+
+        let script = CompiledScript::compile("a or -1").unwrap();
+        assert_eq!(script.eval(&[("a", 5.0)]).unwrap(), 5.0);
+        assert_eq!(script.eval(&[]).unwrap(), -1.0);
+    }
+
+    #[test]
+    fn scripts_are_sandboxed() {
+        for source in [
+            "os.time()",
+            "io.open('x')",
+            "load('return 1')()",
+            "require('os')",
+        ] {
+            let script = CompiledScript::compile(source).unwrap();
+            assert!(script.eval(&[]).is_err(), "{} ran", source);
+        }
+        let script = CompiledScript::compile("while true do end").unwrap();
+        assert!(script.eval(&[]).is_err());
+        // The budget is per evaluation.
+        let script = CompiledScript::compile("math.floor(x)").unwrap();
+        assert_eq!(script.eval(&[("x", 2.5)]).unwrap(), 2.0);
+    }
+
+    #[test]
+    fn reads_script_options() {
+        let rm = MockContext::new();
+        assert!(rm.read_script("Script").is_none());
+        rm.set("Script", "a +");
+        assert!(rm.read_script("Script").is_none());
+        assert_eq!(rm.warnings().len(), 1);
+        rm.set("Script", "a * 2");
+        let script = rm.read_script("Script").unwrap();
+        assert_eq!(script.eval(&[("a", 21.0)]).unwrap(), 42.0);
+    }
+}
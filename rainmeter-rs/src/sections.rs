@@ -0,0 +1,104 @@
+//! Reading options from a list of other sections, for dashboard-style
+//! plugins configured with one section per item.
+
+use crate::RainmeterContext;
+
+/// Options of one section, handed to the callback of
+/// [`for_each_section`](RainmeterContext::for_each_section).
+#[derive(Clone, Copy)]
+pub struct SectionOptions<'a> {
+    rm: &'a RainmeterContext,
+    section: &'a str,
+}
+
+impl SectionOptions<'_> {
+    /// The section's name.
+    pub fn name(&self) -> &str {
+        self.section
+    }
+
+    pub fn read_string(&self, key: &str, default: &str) -> String {
+        self.rm.read_string_section(self.section, key, default)
+    }
+
+    pub fn read_formula(&self, key: &str, default: f64) -> f64 {
+        self.rm.read_formula_section(self.section, key, default)
+    }
+
+    pub fn read_int(&self, key: &str, default: i32) -> i32 {
+        self.rm.read_int_section(self.section, key, default)
+    }
+
+    /// A Rainmeter-style boolean: any non-zero number is `true`.
+    pub fn read_bool(&self, key: &str, default: bool) -> bool {
+        self.read_formula(key, if default { 1.0 } else { 0.0 }) != 0.0
+    }
+}
+
+impl RainmeterContext {
+    /// Read a `|`-separated list of section names, e.g.
+    /// `Items=ItemCPU | ItemRAM | ItemDisk`, for
+    /// [`for_each_section`](Self::for_each_section).
+    pub fn read_section_list(&self, key: &str) -> Vec<String> {
+        self.read_string_list(key, '|')
+    }
+
+    /// Call `read` with the options of each of `sections` in turn and
+    /// collect the results:
+    ///
+    /// ```rust,ignore
+    /// // Items=ItemCPU | ItemRAM, with [ItemCPU] Label=CPU, Source=MeasureCPU ...
+    /// let items = rm.for_each_section(&rm.read_section_list("Items"), |section| Item {
+    ///     label: section.read_string("Label", section.name()),
+    ///     source: section.read_string("Source", ""),
+    /// });
+    /// ```
+    ///
+    /// The sections are ordinary skin sections (meters, measures or plain
+    /// option holders); a missing section reads as all defaults.
+    pub fn for_each_section<T>(
+        &self,
+        sections: &[String],
+        mut read: impl FnMut(SectionOptions<'_>) -> T,
+    ) -> Vec<T> {
+        sections
+            .iter()
+            .map(|section| read(SectionOptions { rm: self, section }))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::MockContext;
+
+    #[test]
+    fn reads_options_from_each_section() {
+        let rm = MockContext::new();
+        rm.set("Items", "ItemCPU | ItemRAM | ItemMissing")
+            .set_in("ItemCPU", "Label", "CPU")
+            .set_in("ItemCPU", "Max", "100")
+            .set_in("ItemCPU", "Enabled", "1")
+            .set_in("ItemRAM", "Label", "RAM")
+            .set_in("ItemRAM", "Max", "16.5");
+        let sections = rm.read_section_list("Items");
+        assert_eq!(sections, ["ItemCPU", "ItemRAM", "ItemMissing"]);
+
+        let items = rm.for_each_section(&sections, |section| {
+            (
+                section.read_string("Label", section.name()),
+                section.read_formula("Max", 1.0),
+                section.read_int("Max", 1),
+                section.read_bool("Enabled", false),
+            )
+        });
+        assert_eq!(
+            items,
+            [
+                ("CPU".to_string(), 100.0, 100, true),
+                ("RAM".to_string(), 16.5, 16, false),
+                ("ItemMissing".to_string(), 1.0, 1, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A keyed cache with per-entry expiry, shared between measures (or threads).
+///
+/// The typical use is one expensive fetch feeding several measures: keep a
+/// `SharedCache` in a `static LazyLock` (or clone it into each measure) and
+/// have every measure call [`get_or_fetch`](Self::get_or_fetch) with the
+/// same key. Only the first caller in each TTL window runs the fetch; the
+/// others get the cached value.
+///
+/// Each key has its own lock, held while that key is fetched: concurrent
+/// callers for the same key wait for the fetch in progress instead of
+/// starting their own, while other keys stay available.
+///
+/// ```rust
+/// # use rainmeter::SharedCache;
+/// # use std::{sync::LazyLock, time::Duration};
+/// static WEATHER: LazyLock<SharedCache<String, String>> = LazyLock::new(SharedCache::new);
+///
+/// let report = WEATHER.get_or_fetch("Oslo".into(), Duration::from_secs(600), || {
+///     "Sunny".to_string()
+/// });
+/// assert_eq!(report, "Sunny");
+/// ```
+pub struct SharedCache<K, V> {
+    entries: Arc<Mutex<HashMap<K, Slot<V>>>>,
+}
+
+/// A key's cached value and when it was fetched, locked on its own so a slow
+/// fetch only blocks callers asking for the same key.
+type Slot<V> = Arc<Mutex<Option<(V, Instant)>>>;
+
+impl<K, V> Clone for SharedCache<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: Arc::clone(&self.entries),
+        }
+    }
+}
+
+impl<K, V> Default for SharedCache<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> SharedCache<K, V> {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the cached value for `key` if it is younger than `ttl`,
+    /// otherwise call `fetch`, cache its result and return it.
+    pub fn get_or_fetch(&self, key: K, ttl: Duration, fetch: impl FnOnce() -> V) -> V {
+        let slot = Arc::clone(
+            self.entries
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(key)
+                .or_default(),
+        );
+        let mut slot = slot.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((value, fetched_at)) = slot.as_ref()
+            && fetched_at.elapsed() < ttl
+        {
+            return value.clone();
+        }
+        let value = fetch();
+        *slot = Some((value.clone(), Instant::now()));
+        value
+    }
+
+    /// Drop the cached value for `key`, forcing the next call to fetch.
+    pub fn invalidate(&self, key: &K) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key);
+    }
+
+    /// Drop all cached values.
+    pub fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Barrier, mpsc};
+    use std::thread;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn concurrent_gets_within_ttl_fetch_once() {
+        let cache = SharedCache::new();
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(Barrier::new(8));
+
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                let (cache, fetches, barrier) =
+                    (cache.clone(), Arc::clone(&fetches), Arc::clone(&barrier));
+                thread::spawn(move || {
+                    barrier.wait();
+                    cache.get_or_fetch("weather", TTL, || {
+                        fetches.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(50));
+                        "Sunny"
+                    })
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            assert_eq!(worker.join().unwrap(), "Sunny");
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn slow_fetch_does_not_block_other_keys() {
+        let cache = SharedCache::new();
+        let (started_tx, started_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let slow = {
+            let cache = cache.clone();
+            thread::spawn(move || {
+                cache.get_or_fetch("slow", TTL, || {
+                    started_tx.send(()).unwrap();
+                    release_rx.recv().unwrap();
+                    1
+                })
+            })
+        };
+
+        started_rx.recv().unwrap();
+        assert_eq!(cache.get_or_fetch("fast", TTL, || 2), 2);
+        release_tx.send(()).unwrap();
+        assert_eq!(slow.join().unwrap(), 1);
+    }
+
+    #[test]
+    fn expired_and_invalidated_entries_are_refetched() {
+        let cache = SharedCache::new();
+        assert_eq!(cache.get_or_fetch(1, TTL, || "a"), "a");
+        assert_eq!(cache.get_or_fetch(1, TTL, || "b"), "a");
+        assert_eq!(cache.get_or_fetch(1, Duration::ZERO, || "c"), "c");
+
+        cache.invalidate(&1);
+        assert_eq!(cache.get_or_fetch(1, TTL, || "d"), "d");
+        cache.clear();
+        assert_eq!(cache.get_or_fetch(1, TTL, || "e"), "e");
+    }
+}
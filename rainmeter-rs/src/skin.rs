@@ -0,0 +1,127 @@
+//! Helpers for information about the skin the measure lives in.
+
+use crate::RainmeterContext;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher` its output is fixed across Rust
+/// versions, so it's safe to use in names that outlive the session.
+pub(crate) fn fnv1a64(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        // NUL separator so ("ab", "c") and ("a", "bc") hash differently.
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+impl RainmeterContext {
+    /// Expand `expr` via `replace_variables`, returning `None` if Rainmeter
+    /// left it untouched (i.e. the variable doesn't exist) or it resolved to
+    /// an empty string.
+    pub(crate) fn resolve_variable(&self, expr: &str) -> Option<String> {
+        let resolved = self.replace_variables(expr);
+        if resolved.is_empty() || resolved == expr {
+            None
+        } else {
+            Some(resolved)
+        }
+    }
+
+    /// The config name of the skin (`#CURRENTCONFIG#`), e.g. `illustro\Clock`.
+    ///
+    /// Unlike [`get_skin_name`](Self::get_skin_name) this is stable across
+    /// skin variants, which makes it a good key for per-skin persisted state.
+    /// Returns an empty string if the variable can't be resolved.
+    pub fn current_config(&self) -> String {
+        self.resolve_variable("#CURRENTCONFIG#").unwrap_or_default()
+    }
+
+    /// A stable identifier for this measure, usable as a file name (e.g. for
+    /// per-measure caches or temp files).
+    ///
+    /// It is derived from the settings file path, the skin config and the
+    /// measure name, so it is the same across reloads and sessions but differs
+    /// between measures, even ones with the same name in different skins.
+    /// Looks like `MeasureWeather-1f3a...`.
+    pub fn measure_id(&self) -> String {
+        let measure = self.get_measure_name();
+        let hash = fnv1a64(&[
+            self.get_settings_file_cached(),
+            &self.current_config(),
+            &measure,
+        ]);
+        let readable: String = measure
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .take(32)
+            .collect();
+        format!("{}-{:016x}", readable, hash)
+    }
+
+    /// Whether the skin window is currently shown. Rainmeter keeps calling
+    /// `update` for hidden skins (e.g. after `!Hide`), so plugins can use
+    /// this to skip expensive work nobody would see:
+    ///
+    /// ```rust,ignore
+    /// fn update(&mut self, rm: RainmeterContext) -> f64 {
+    ///     if !rm.is_skin_visible() {
+    ///         return self.last_value;
+    ///     }
+    ///     self.last_value = self.expensive_query();
+    ///     self.last_value
+    /// }
+    /// ```
+    ///
+    /// A skin that is covered by other windows, or faded out by "Hide on
+    /// mouse over", still counts as visible. Returns `false` if Rainmeter
+    /// provides no window handle.
+    pub fn is_skin_visible(&self) -> bool {
+        let hwnd = self.get_skin_window();
+        !hwnd.is_invalid() && unsafe { IsWindowVisible(hwnd) }.as_bool()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::MockContext;
+
+    #[test]
+    fn current_config_reads_the_variable() {
+        let rm = MockContext::new();
+        assert_eq!(rm.current_config(), "");
+        rm.set_variable("#CURRENTCONFIG#", "illustro\\Clock");
+        assert_eq!(rm.current_config(), "illustro\\Clock");
+    }
+
+    #[test]
+    fn measure_ids_are_stable_and_distinct() {
+        let rm = MockContext::new();
+        rm.set_variable("#CURRENTCONFIG#", "illustro\\Clock");
+        let clock = rm.measure_id();
+        assert!(clock.starts_with("MeasureTest-"), "{}", clock);
+        assert_eq!(rm.measure_id(), clock);
+
+        rm.set_measure_name("MeasureWeather");
+        let weather = rm.measure_id();
+        assert!(weather.starts_with("MeasureWeather-"), "{}", weather);
+        assert_ne!(weather, clock);
+
+        // Same name in another skin.
+        rm.set_measure_name("MeasureTest")
+            .set_variable("#CURRENTCONFIG#", "illustro\\Disk");
+        assert_ne!(rm.measure_id(), clock);
+
+        rm.set_measure_name("Measure Ä/1");
+        assert!(rm.measure_id().starts_with("Measure1-"));
+    }
+
+    #[test]
+    fn skins_without_a_window_are_not_visible() {
+        let rm = MockContext::new();
+        rm.set_skin_window(0);
+        assert!(!rm.is_skin_visible());
+    }
+}
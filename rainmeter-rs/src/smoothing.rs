@@ -0,0 +1,129 @@
+use crate::{OptionEnum, RainmeterContext};
+use std::collections::VecDeque;
+
+/// Largest window [`read_smoothing`](RainmeterContext::read_smoothing)
+/// accepts.
+const MAX_WINDOW: i32 = 1000;
+
+/// How a [`Smoother`] averages values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmoothingMethod {
+    /// Simple moving average: the mean of the last `window` values.
+    Sma,
+    /// Exponential moving average with `alpha = 2 / (window + 1)`, which
+    /// reacts like an SMA of the same window but needs no history.
+    Ema,
+}
+
+impl OptionEnum for SmoothingMethod {
+    const NAMES: &'static [(&'static str, Self)] =
+        &[("SMA", SmoothingMethod::Sma), ("EMA", SmoothingMethod::Ema)];
+}
+
+/// Smooths a noisy series of readings, fed one value per update.
+///
+/// ```rust
+/// # use rainmeter::{Smoother, SmoothingMethod};
+/// let mut sma = Smoother::new(SmoothingMethod::Sma, 3);
+/// let smoothed: Vec<f64> = [3.0, 6.0, 9.0, 12.0].map(|v| sma.push(v)).into();
+/// assert_eq!(smoothed, [3.0, 4.5, 6.0, 9.0]);
+///
+/// let mut ema = Smoother::new(SmoothingMethod::Ema, 3);
+/// let smoothed: Vec<f64> = [4.0, 8.0, 8.0].map(|v| ema.push(v)).into();
+/// assert_eq!(smoothed, [4.0, 6.0, 7.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Smoother {
+    method: SmoothingMethod,
+    window: usize,
+    /// The last `window` values (SMA only).
+    values: VecDeque<f64>,
+    current: Option<f64>,
+}
+
+impl Smoother {
+    /// Create a smoother averaging over `window` values (at least one; a
+    /// window of one passes values through unchanged).
+    pub fn new(method: SmoothingMethod, window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            method,
+            window,
+            values: VecDeque::with_capacity(if method == SmoothingMethod::Sma {
+                window
+            } else {
+                0
+            }),
+            current: None,
+        }
+    }
+
+    /// Feed the next reading and return the smoothed value. The first
+    /// reading is returned as is; an SMA averages over the readings so far
+    /// until the window fills.
+    pub fn push(&mut self, v: f64) -> f64 {
+        let smoothed = match (self.method, self.current) {
+            (SmoothingMethod::Sma, _) => {
+                if self.values.len() == self.window {
+                    self.values.pop_front();
+                }
+                self.values.push_back(v);
+                self.values.iter().sum::<f64>() / self.values.len() as f64
+            }
+            (SmoothingMethod::Ema, None) => v,
+            (SmoothingMethod::Ema, Some(previous)) => {
+                let alpha = 2.0 / (self.window as f64 + 1.0);
+                previous + alpha * (v - previous)
+            }
+        };
+        self.current = Some(smoothed);
+        smoothed
+    }
+
+    /// The last smoothed value, or `None` before the first
+    /// [`push`](Self::push).
+    pub fn value(&self) -> Option<f64> {
+        self.current
+    }
+
+    pub fn method(&self) -> SmoothingMethod {
+        self.method
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Forget all readings, e.g. after a gap in the data.
+    pub fn reset(&mut self) {
+        self.values.clear();
+        self.current = None;
+    }
+}
+
+impl RainmeterContext {
+    /// Read a [`Smoother`] from a window size option (e.g.
+    /// `SmoothWindow=5`, clamped to 1-1000, default 1 for no smoothing) and
+    /// a method option (`SmoothMethod=SMA` or `EMA`, default SMA).
+    ///
+    /// The new smoother starts empty, so keep the old one if the settings
+    /// didn't change; otherwise `DynamicVariables=1` resets it every update.
+    ///
+    /// ```rust,ignore
+    /// fn reload(&mut self, rm: RainmeterContext, _max: &mut f64) {
+    ///     let smoother = rm.read_smoothing("SmoothWindow", "SmoothMethod");
+    ///     if (smoother.method(), smoother.window()) != (self.smoother.method(), self.smoother.window()) {
+    ///         self.smoother = smoother;
+    ///     }
+    /// }
+    ///
+    /// fn update(&mut self, _rm: RainmeterContext) -> f64 {
+    ///     self.smoother.push(self.sample())
+    /// }
+    /// ```
+    pub fn read_smoothing(&self, window_key: &str, method_key: &str) -> Smoother {
+        let window = self.read_clamped_int(window_key, 1, 1, MAX_WINDOW);
+        let method = self.read_enum(method_key, SmoothingMethod::Sma);
+        Smoother::new(method, window as usize)
+    }
+}
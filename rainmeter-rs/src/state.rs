@@ -0,0 +1,110 @@
+//! Versioned persisted state.
+//!
+//! Plugins that persist their own state (to a file next to the skin, a
+//! Rainmeter variable, ...) can wrap it with [`encode_state`] and read it back
+//! with [`decode_state`], which upgrades older blobs through
+//! [`StateSchema::migrate`] so the stored format can evolve without losing
+//! user data.
+//!
+//! ## Migration contract
+//!
+//! - Stored blobs look like `{"version": N, "data": ...}`. Blobs without that
+//!   envelope (written before versioning was adopted) are treated as version 0.
+//! - `migrate(from, value)` receives data at version `from` and must return it
+//!   at version `from + 1`. Migrations run one step at a time until
+//!   [`StateSchema::VERSION`] is reached, so each step only has to know about
+//!   its immediate predecessor.
+//! - Blobs newer than `VERSION` (written by a newer plugin build) are rejected
+//!   rather than guessed at.
+
+use crate::RmError;
+use serde_json::{Value as JsonValue, json};
+
+/// Describes the current shape of a plugin's persisted state.
+pub trait StateSchema {
+    /// Version written by [`encode_state`].
+    const VERSION: u32;
+
+    /// Upgrade `value` from version `from` to version `from + 1`.
+    fn migrate(from: u32, value: JsonValue) -> JsonValue;
+}
+
+/// Wrap `data` in a versioned envelope and serialize it.
+pub fn encode_state<S: StateSchema>(data: &JsonValue) -> String {
+    json!({ "version": S::VERSION, "data": data }).to_string()
+}
+
+/// Parse a blob written by [`encode_state`] (by this or any older version of
+/// the plugin) and migrate it up to [`StateSchema::VERSION`].
+pub fn decode_state<S: StateSchema>(raw: &str) -> Result<JsonValue, RmError> {
+    let stored: JsonValue = serde_json::from_str(raw)
+        .map_err(|err| RmError::InvalidState(format!("not valid JSON: {}", err)))?;
+
+    let (mut version, mut data) = match stored {
+        JsonValue::Object(mut map) if map.contains_key("version") && map.contains_key("data") => {
+            let version = map
+                .get("version")
+                .and_then(JsonValue::as_u64)
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| RmError::InvalidState("invalid 'version' field".into()))?;
+            (version, map.remove("data").unwrap_or(JsonValue::Null))
+        }
+        other => (0, other),
+    };
+
+    if version > S::VERSION {
+        return Err(RmError::InvalidState(format!(
+            "stored version {} is newer than supported version {}",
+            version,
+            S::VERSION
+        )));
+    }
+    while version < S::VERSION {
+        data = S::migrate(version, data);
+        version += 1;
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// v1 stored `{"city": "..."}`; v2 renamed it to `location`.
+    struct Weather;
+
+    impl StateSchema for Weather {
+        const VERSION: u32 = 2;
+
+        fn migrate(from: u32, mut value: JsonValue) -> JsonValue {
+            if from == 1 {
+                let city = value.as_object_mut().and_then(|map| map.remove("city"));
+                value["location"] = city.unwrap_or(JsonValue::Null);
+            }
+            value
+        }
+    }
+
+    #[test]
+    fn migrates_v1_blob_to_v2() {
+        let v1 = r#"{"version": 1, "data": {"city": "Oslo", "units": "metric"}}"#;
+        let data = decode_state::<Weather>(v1).unwrap();
+        assert_eq!(data, json!({ "location": "Oslo", "units": "metric" }));
+    }
+
+    #[test]
+    fn current_version_round_trips() {
+        let data = json!({ "location": "Oslo" });
+        let blob = encode_state::<Weather>(&data);
+        assert_eq!(decode_state::<Weather>(&blob).unwrap(), data);
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let v3 = r#"{"version": 3, "data": {}}"#;
+        assert!(matches!(
+            decode_state::<Weather>(v3),
+            Err(RmError::InvalidState(_))
+        ));
+    }
+}
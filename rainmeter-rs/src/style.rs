@@ -0,0 +1,173 @@
+use crate::{Condition, RainmeterContext};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// One `condition:value` entry of a style option, e.g. `>90:Red`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleRule {
+    /// The threshold to test, or `None` for the catch-all `*` rule.
+    pub condition: Option<Condition>,
+    pub value: String,
+}
+
+impl StyleRule {
+    /// Parse `condition:value`, where `condition` is `*` or a
+    /// [`Condition`] like `>= 90`.
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (condition, value) = entry.split_once(':')?;
+        let condition = match condition.trim() {
+            "*" => None,
+            other => Some(Condition::parse(other)?),
+        };
+        Some(Self {
+            condition,
+            value: value.trim().to_string(),
+        })
+    }
+
+    pub fn matches(&self, value: f64) -> bool {
+        self.condition.is_none_or(|c| c.eval(value))
+    }
+}
+
+/// An ordered list of [`StyleRule`]s; the first matching rule wins.
+///
+/// Derefs to the underlying `Vec`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StyleRules {
+    rules: Vec<StyleRule>,
+}
+
+impl StyleRules {
+    /// The value of the first rule matching `value`, or `None` if no rule
+    /// matches (add a `*` rule as a fallback to avoid that).
+    pub fn resolve(&self, value: f64) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(value))
+            .map(|rule| rule.value.as_str())
+    }
+}
+
+impl Deref for StyleRules {
+    type Target = Vec<StyleRule>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rules
+    }
+}
+
+/// Value-dependent icons: like [`StyleRules`], but each value is an image
+/// path resolved relative to the skin.
+///
+/// Derefs to the underlying `Vec` of `(condition, path)` pairs, where a
+/// `None` condition is the catch-all `*` entry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IconMap {
+    icons: Vec<(Option<Condition>, PathBuf)>,
+}
+
+impl IconMap {
+    /// The icon of the first entry matching `value`, or `None` if nothing
+    /// matches.
+    pub fn resolve(&self, value: f64) -> Option<&Path> {
+        self.icons
+            .iter()
+            .find(|(condition, _)| condition.is_none_or(|c| c.eval(value)))
+            .map(|(_, path)| path.as_path())
+    }
+}
+
+impl Deref for IconMap {
+    type Target = Vec<(Option<Condition>, PathBuf)>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.icons
+    }
+}
+
+impl RainmeterContext {
+    /// Read a `|`-separated list of conditional styles, such as
+    /// `Styles=>90:Red | >50:Yellow | *:Green`. Rules are tried in order, so
+    /// put `*` last. Malformed entries are logged and skipped.
+    pub fn read_style_rules(&self, key: &str) -> StyleRules {
+        StyleRules {
+            rules: self.read_list_schema(key, '|', StyleRule::parse),
+        }
+    }
+
+    /// Read a `|`-separated list of conditional icons, such as
+    /// `Icons=>90:alert.png | >50:warn.png | *:ok.png`, using the same syntax
+    /// as [`read_style_rules`](Self::read_style_rules). Paths are resolved
+    /// with `path_to_absolute`; whether the files exist isn't checked.
+    pub fn read_icon_map(&self, key: &str) -> IconMap {
+        IconMap {
+            icons: self
+                .read_list_schema(key, '|', StyleRule::parse)
+                .into_iter()
+                .map(|rule| {
+                    let path = PathBuf::from(self.path_to_absolute(&rule.value));
+                    (rule.condition, path)
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::MockContext;
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rm = MockContext::new();
+        rm.set("Styles", ">90:Red | >50:Yellow | >70:Orange | *:Green");
+        let rules = rm.read_style_rules("Styles");
+        assert_eq!(rules.len(), 4);
+        assert_eq!(rules.resolve(95.0), Some("Red"));
+        // `>50` comes before `>70`, so `Orange` never wins.
+        assert_eq!(rules.resolve(75.0), Some("Yellow"));
+        assert_eq!(rules.resolve(10.0), Some("Green"));
+        assert!(rm.warnings().is_empty());
+    }
+
+    #[test]
+    fn malformed_rules_are_skipped() {
+        let rm = MockContext::new();
+        rm.set("Styles", ">90:Red | 50:Yellow | >>1:Blue");
+        let rules = rm.read_style_rules("Styles");
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules.resolve(95.0), Some("Red"));
+        // No `*` rule to fall back on.
+        assert_eq!(rules.resolve(10.0), None);
+        assert_eq!(rm.warnings().len(), 2);
+    }
+
+    #[test]
+    fn icons_resolve_relative_to_the_skin() {
+        let skin = std::env::temp_dir().join("IconSkin");
+        let rm = MockContext::new();
+        rm.set_skin_path(&skin).set(
+            "Icons",
+            r">90:alert.png | >50:Icons\warn.png | *:C:\icons\ok.png",
+        );
+        let icons = rm.read_icon_map("Icons");
+        assert_eq!(icons.len(), 3);
+        assert_eq!(icons.resolve(95.0), Some(skin.join("alert.png").as_path()));
+        assert_eq!(
+            icons.resolve(60.0),
+            Some(skin.join(r"Icons\warn.png").as_path())
+        );
+        // Only the first `:` separates the condition from the path.
+        let fallback = icons.resolve(10.0).unwrap().to_string_lossy();
+        assert!(fallback.ends_with(r"C:\icons\ok.png"), "{}", fallback);
+        assert!(icons[2].0.is_none());
+    }
+
+    #[test]
+    fn icons_without_a_fallback() {
+        let rm = MockContext::new();
+        rm.set("Icons", ">90:alert.png");
+        assert_eq!(rm.read_icon_map("Icons").resolve(10.0), None);
+    }
+}
@@ -0,0 +1,216 @@
+//! Regex substitution rules, behind the `regex` feature.
+
+use crate::{RainmeterContext, RmLogLevel};
+use regex::{Regex, RegexBuilder};
+use std::ops::Deref;
+
+/// Ordered `(pattern, replacement)` rules read by
+/// [`read_regex_substitutions`](RainmeterContext::read_regex_substitutions).
+///
+/// Derefs to the underlying `Vec`.
+#[derive(Debug, Clone, Default)]
+pub struct RegexSubstitutions {
+    rules: Vec<(Regex, String)>,
+}
+
+impl RegexSubstitutions {
+    /// Run every rule over `input` in order, each replacing all matches.
+    /// Replacements can refer to groups as `$1` or `${name}`.
+    pub fn apply(&self, input: &str) -> String {
+        self.rules
+            .iter()
+            .fold(input.to_string(), |text, (regex, replacement)| {
+                regex.replace_all(&text, replacement.as_str()).into_owned()
+            })
+    }
+}
+
+impl Deref for RegexSubstitutions {
+    type Target = Vec<(Regex, String)>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rules
+    }
+}
+
+/// Read up to the next unescaped `/`, unescaping `\/`. Other escapes are
+/// kept for the regex engine. Returns the part and the text after the `/`.
+fn take_part(input: &str) -> Option<(String, &str)> {
+    let mut part = String::new();
+    let mut chars = input.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '/' => return Some((part, &input[i + 1..])),
+            '\\' => match chars.next() {
+                Some((_, '/')) => part.push('/'),
+                Some((_, next)) => {
+                    part.push('\\');
+                    part.push(next);
+                }
+                None => part.push('\\'),
+            },
+            c => part.push(c),
+        }
+    }
+    None
+}
+
+/// Split `input` into rules of the form `/pattern/replacement/flags`,
+/// separated by `|`. Since `|` is also regex alternation, rules are scanned
+/// rather than split. Returns parsed `(pattern, replacement, flags)` or the
+/// malformed text.
+fn parse_rules(input: &str) -> Vec<Result<(String, String, String), String>> {
+    let mut rules = Vec::new();
+    let mut rest = input.trim_start();
+    while !rest.is_empty() {
+        let parsed = rest.strip_prefix('/').and_then(|r| {
+            let (pattern, r) = take_part(r)?;
+            let (replacement, r) = take_part(r)?;
+            let flags_len = r
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(r.len());
+            Some((
+                (pattern, replacement, r[..flags_len].to_string()),
+                &r[flags_len..],
+            ))
+        });
+        match parsed {
+            Some((rule, r)) => {
+                rules.push(Ok(rule));
+                rest = r.trim_start();
+                rest = rest.strip_prefix('|').unwrap_or(rest).trim_start();
+            }
+            None => {
+                // Resynchronize at the next rule boundary: a `|` followed by
+                // a `/`, possibly with whitespace between them.
+                let end = rest
+                    .match_indices('|')
+                    .map(|(i, _)| i)
+                    .find(|&i| rest[i + 1..].trim_start().starts_with('/'))
+                    .unwrap_or(rest.len());
+                rules.push(Err(rest[..end].trim().to_string()));
+                rest = rest[end..].strip_prefix('|').unwrap_or("").trim_start();
+            }
+        }
+    }
+    rules
+}
+
+impl RainmeterContext {
+    /// Read sed-style substitutions such as
+    /// `Clean=/\s+/ / | /^(\w+), (\w+)$/$2 $1/ | /colou?r/hue/i`.
+    ///
+    /// Each rule is `/pattern/replacement/` with optional flags after the
+    /// last `/` (`i` for case-insensitive). Write `\/` for a literal `/`.
+    /// Malformed rules and invalid patterns are logged and skipped.
+    pub fn read_regex_substitutions(&self, key: &str) -> RegexSubstitutions {
+        let value = self.read_string(key, "");
+        let mut rules = Vec::new();
+        for rule in parse_rules(&value) {
+            let (pattern, replacement, flags) = match rule {
+                Ok(rule) => rule,
+                Err(raw) => {
+                    self.log(
+                        RmLogLevel::LogWarning,
+                        &format!("{}: skipping malformed rule '{}'", key, raw),
+                    );
+                    continue;
+                }
+            };
+            if let Some(flag) = flags.chars().find(|&f| f != 'i') {
+                self.log(
+                    RmLogLevel::LogWarning,
+                    &format!("{}: ignoring unknown flag '{}' on /{}/", key, flag, pattern),
+                );
+            }
+            match RegexBuilder::new(&pattern)
+                .case_insensitive(flags.contains('i'))
+                .build()
+            {
+                Ok(regex) => rules.push((regex, replacement)),
+                Err(err) => self.log(
+                    RmLogLevel::LogWarning,
+                    &format!("{}: skipping invalid pattern /{}/: {}", key, pattern, err),
+                ),
+            }
+        }
+        RegexSubstitutions { rules }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    fn rule(
+        pattern: &str,
+        replacement: &str,
+        flags: &str,
+    ) -> Result<(String, String, String), String> {
+        Ok((
+            pattern.to_string(),
+            replacement.to_string(),
+            flags.to_string(),
+        ))
+    }
+
+    #[test]
+    fn parses_rules_in_order() {
+        assert_eq!(
+            parse_rules(r"/a|b/x/ | /c\/d/e\/f/i|/g/h/"),
+            [
+                rule("a|b", "x", ""),
+                rule("c/d", "e/f", "i"),
+                rule("g", "h", "")
+            ]
+        );
+        assert_eq!(parse_rules(r"/\d+\\/n/"), [rule(r"\d+\\", "n", "")]);
+        assert!(parse_rules("  ").is_empty());
+    }
+
+    #[test]
+    fn resyncs_after_a_malformed_rule() {
+        assert_eq!(
+            parse_rules("oops | /a/b/ | s/x/y | /c/d/"),
+            [
+                Err("oops".to_string()),
+                rule("a", "b", ""),
+                Err("s/x/y".to_string()),
+                rule("c", "d", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn applies_substitutions_in_order() {
+        let rm = MockContext::new();
+        rm.set(
+            "Clean",
+            r"/\s+/ / | /^(\w+), (\w+)$/$2 $1/ | /colou?r/hue/i | /a\/b/a or b/",
+        );
+        let subs = rm.read_regex_substitutions("Clean");
+        assert_eq!(subs.len(), 4);
+        assert_eq!(subs.apply("Doe,   John"), "John Doe");
+        assert_eq!(subs.apply("COLOUR a/b"), "hue a or b");
+        assert!(rm.warnings().is_empty());
+    }
+
+    #[test]
+    fn logs_bad_flags_and_patterns() {
+        let rm = MockContext::new();
+        rm.set("Clean", "/a/b/ix | /(/x/ | oops | /c/d/");
+        let subs = rm.read_regex_substitutions("Clean");
+        assert_eq!(subs.len(), 2);
+        assert_eq!(subs.apply("A c"), "b d");
+        let warnings = rm.warnings();
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        assert_eq!(warnings[0], "Clean: ignoring unknown flag 'x' on /a/");
+        assert!(
+            warnings[1].starts_with("Clean: skipping invalid pattern /(/"),
+            "{}",
+            warnings[1]
+        );
+        assert_eq!(warnings[2], "Clean: skipping malformed rule 'oops'");
+    }
+}
@@ -0,0 +1,92 @@
+use crate::RainmeterContext;
+
+/// A scripted sequence of bangs, each scheduled at an offset from the start.
+///
+/// The whole timeline is sent to Rainmeter as one action, using `!Delay`
+/// between steps, so it keeps running after the call returns.
+///
+/// ```rust,ignore
+/// Timeline::new()
+///     .at(0, "!ShowMeter Toast")
+///     .at(3000, "!HideMeter Toast")
+///     .execute(&rm);
+/// // runs: [!ShowMeter Toast][!Delay 3000][!HideMeter Toast]
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Timeline {
+    steps: Vec<(u64, String)>,
+}
+
+impl Timeline {
+    /// Create an empty timeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedule `command` to run `delay_ms` milliseconds after the start.
+    /// Steps may be added in any order; steps with the same offset run in the
+    /// order they were added. `command` may be bare (`!Redraw`) or already
+    /// bracketed (`[!Redraw]`, or several bangs `[!A][!B]`).
+    pub fn at(mut self, delay_ms: u64, command: &str) -> Self {
+        self.steps.push((delay_ms, command.trim().to_string()));
+        self
+    }
+
+    /// The composed action, e.g. `[!A][!Delay 500][!B]`.
+    pub fn compose(&self) -> String {
+        let mut steps: Vec<&(u64, String)> = self.steps.iter().collect();
+        // Stable sort keeps insertion order for equal offsets.
+        steps.sort_by_key(|(at, _)| *at);
+
+        let mut action = String::new();
+        let mut elapsed = 0;
+        for (at, command) in steps {
+            if *at > elapsed {
+                action.push_str(&format!("[!Delay {}]", at - elapsed));
+                elapsed = *at;
+            }
+            if command.starts_with('[') {
+                action.push_str(command);
+            } else {
+                action.push_str(&format!("[{}]", command));
+            }
+        }
+        action
+    }
+
+    /// Send the timeline to Rainmeter in a single `RmExecute` call.
+    pub fn execute(&self, rm: &RainmeterContext) {
+        if !self.steps.is_empty() {
+            rm.execute(&self.compose());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn out_of_order_steps_are_sorted() {
+        let timeline = Timeline::new()
+            .at(3000, "!HideMeter Toast")
+            .at(0, "!ShowMeter Toast")
+            .at(500, "[!A][!B]")
+            .at(500, " !Redraw ");
+        assert_eq!(
+            timeline.compose(),
+            "[!ShowMeter Toast][!Delay 500][!A][!B][!Redraw][!Delay 2500][!HideMeter Toast]"
+        );
+    }
+
+    #[test]
+    fn executes_as_one_action() {
+        let rm = MockContext::new();
+        Timeline::new().execute(&rm);
+        assert!(rm.bangs().is_empty());
+
+        Timeline::new().at(100, "!A").execute(&rm);
+        assert_eq!(rm.bangs(), ["[!Delay 100][!A]"]);
+    }
+}
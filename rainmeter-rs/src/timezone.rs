@@ -0,0 +1,454 @@
+//! Time zone options for world-clock style plugins.
+
+use crate::{RainmeterContext, RmLogLevel};
+use std::time::{SystemTime, UNIX_EPOCH};
+use windows::Win32::System::SystemInformation::{GetLocalTime, GetSystemTime};
+use windows::Win32::System::Time::{
+    DYNAMIC_TIME_ZONE_INFORMATION, EnumDynamicTimeZoneInformation,
+    SystemTimeToTzSpecificLocalTimeEx,
+};
+
+/// `EnumDynamicTimeZoneInformation`'s "no more entries" status.
+const ERROR_NO_MORE_ITEMS: u32 = 259;
+
+/// IANA zone names and the Windows time zone ID each maps to, following
+/// the CLDR `windowsZones` table: every Windows zone's primary IANA name,
+/// plus the names of other large cities.
+const IANA_ZONES: &[(&str, &str)] = &[
+    ("Etc/GMT+12", "Dateline Standard Time"),
+    ("Etc/GMT+11", "UTC-11"),
+    ("America/Adak", "Aleutian Standard Time"),
+    ("Pacific/Honolulu", "Hawaiian Standard Time"),
+    ("Pacific/Marquesas", "Marquesas Standard Time"),
+    ("America/Anchorage", "Alaskan Standard Time"),
+    ("Etc/GMT+9", "UTC-09"),
+    ("America/Tijuana", "Pacific Standard Time (Mexico)"),
+    ("Etc/GMT+8", "UTC-08"),
+    ("America/Los_Angeles", "Pacific Standard Time"),
+    ("America/Vancouver", "Pacific Standard Time"),
+    ("America/Phoenix", "US Mountain Standard Time"),
+    ("America/Mazatlan", "Mountain Standard Time (Mexico)"),
+    ("America/Denver", "Mountain Standard Time"),
+    ("America/Edmonton", "Mountain Standard Time"),
+    ("America/Whitehorse", "Yukon Standard Time"),
+    ("America/Guatemala", "Central America Standard Time"),
+    ("America/Chicago", "Central Standard Time"),
+    ("America/Winnipeg", "Central Standard Time"),
+    ("Pacific/Easter", "Easter Island Standard Time"),
+    ("America/Mexico_City", "Central Standard Time (Mexico)"),
+    ("America/Regina", "Canada Central Standard Time"),
+    ("America/Bogota", "SA Pacific Standard Time"),
+    ("America/Lima", "SA Pacific Standard Time"),
+    ("America/Cancun", "Eastern Standard Time (Mexico)"),
+    ("America/New_York", "Eastern Standard Time"),
+    ("America/Toronto", "Eastern Standard Time"),
+    ("America/Detroit", "Eastern Standard Time"),
+    ("America/Port-au-Prince", "Haiti Standard Time"),
+    ("America/Havana", "Cuba Standard Time"),
+    ("America/Indianapolis", "US Eastern Standard Time"),
+    ("America/Indiana/Indianapolis", "US Eastern Standard Time"),
+    ("America/Grand_Turk", "Turks And Caicos Standard Time"),
+    ("America/Asuncion", "Paraguay Standard Time"),
+    ("America/Halifax", "Atlantic Standard Time"),
+    ("America/Caracas", "Venezuela Standard Time"),
+    ("America/Cuiaba", "Central Brazilian Standard Time"),
+    ("America/La_Paz", "SA Western Standard Time"),
+    ("America/Santiago", "Pacific SA Standard Time"),
+    ("America/St_Johns", "Newfoundland Standard Time"),
+    ("America/Araguaina", "Tocantins Standard Time"),
+    ("America/Sao_Paulo", "E. South America Standard Time"),
+    ("America/Cayenne", "SA Eastern Standard Time"),
+    ("America/Buenos_Aires", "Argentina Standard Time"),
+    ("America/Argentina/Buenos_Aires", "Argentina Standard Time"),
+    ("America/Godthab", "Greenland Standard Time"),
+    ("America/Nuuk", "Greenland Standard Time"),
+    ("America/Montevideo", "Montevideo Standard Time"),
+    ("America/Punta_Arenas", "Magallanes Standard Time"),
+    ("America/Miquelon", "Saint Pierre Standard Time"),
+    ("America/Bahia", "Bahia Standard Time"),
+    ("Etc/GMT+2", "UTC-02"),
+    ("Atlantic/Azores", "Azores Standard Time"),
+    ("Atlantic/Cape_Verde", "Cape Verde Standard Time"),
+    ("Etc/UTC", "UTC"),
+    ("Europe/London", "GMT Standard Time"),
+    ("Europe/Dublin", "GMT Standard Time"),
+    ("Europe/Lisbon", "GMT Standard Time"),
+    ("Atlantic/Reykjavik", "Greenwich Standard Time"),
+    ("Africa/Sao_Tome", "Sao Tome Standard Time"),
+    ("Africa/Casablanca", "Morocco Standard Time"),
+    ("Europe/Berlin", "W. Europe Standard Time"),
+    ("Europe/Amsterdam", "W. Europe Standard Time"),
+    ("Europe/Oslo", "W. Europe Standard Time"),
+    ("Europe/Rome", "W. Europe Standard Time"),
+    ("Europe/Stockholm", "W. Europe Standard Time"),
+    ("Europe/Vienna", "W. Europe Standard Time"),
+    ("Europe/Zurich", "W. Europe Standard Time"),
+    ("Europe/Budapest", "Central Europe Standard Time"),
+    ("Europe/Prague", "Central Europe Standard Time"),
+    ("Europe/Paris", "Romance Standard Time"),
+    ("Europe/Brussels", "Romance Standard Time"),
+    ("Europe/Copenhagen", "Romance Standard Time"),
+    ("Europe/Madrid", "Romance Standard Time"),
+    ("Europe/Warsaw", "Central European Standard Time"),
+    ("Africa/Lagos", "W. Central Africa Standard Time"),
+    ("Asia/Amman", "Jordan Standard Time"),
+    ("Europe/Athens", "GTB Standard Time"),
+    ("Europe/Bucharest", "GTB Standard Time"),
+    ("Asia/Beirut", "Middle East Standard Time"),
+    ("Africa/Cairo", "Egypt Standard Time"),
+    ("Europe/Chisinau", "E. Europe Standard Time"),
+    ("Asia/Damascus", "Syria Standard Time"),
+    ("Asia/Hebron", "West Bank Standard Time"),
+    ("Africa/Johannesburg", "South Africa Standard Time"),
+    ("Europe/Helsinki", "FLE Standard Time"),
+    ("Europe/Kiev", "FLE Standard Time"),
+    ("Europe/Kyiv", "FLE Standard Time"),
+    ("Asia/Jerusalem", "Israel Standard Time"),
+    ("Africa/Juba", "South Sudan Standard Time"),
+    ("Europe/Kaliningrad", "Kaliningrad Standard Time"),
+    ("Africa/Khartoum", "Sudan Standard Time"),
+    ("Africa/Tripoli", "Libya Standard Time"),
+    ("Africa/Windhoek", "Namibia Standard Time"),
+    ("Asia/Baghdad", "Arabic Standard Time"),
+    ("Europe/Istanbul", "Turkey Standard Time"),
+    ("Asia/Riyadh", "Arab Standard Time"),
+    ("Europe/Minsk", "Belarus Standard Time"),
+    ("Europe/Moscow", "Russian Standard Time"),
+    ("Africa/Nairobi", "E. Africa Standard Time"),
+    ("Europe/Volgograd", "Volgograd Standard Time"),
+    ("Asia/Tehran", "Iran Standard Time"),
+    ("Asia/Dubai", "Arabian Standard Time"),
+    ("Europe/Astrakhan", "Astrakhan Standard Time"),
+    ("Asia/Baku", "Azerbaijan Standard Time"),
+    ("Europe/Samara", "Russia Time Zone 3"),
+    ("Indian/Mauritius", "Mauritius Standard Time"),
+    ("Europe/Saratov", "Saratov Standard Time"),
+    ("Asia/Tbilisi", "Georgian Standard Time"),
+    ("Asia/Yerevan", "Caucasus Standard Time"),
+    ("Asia/Kabul", "Afghanistan Standard Time"),
+    ("Asia/Tashkent", "West Asia Standard Time"),
+    ("Asia/Qyzylorda", "Qyzylorda Standard Time"),
+    ("Asia/Yekaterinburg", "Ekaterinburg Standard Time"),
+    ("Asia/Karachi", "Pakistan Standard Time"),
+    ("Asia/Calcutta", "India Standard Time"),
+    ("Asia/Kolkata", "India Standard Time"),
+    ("Asia/Colombo", "Sri Lanka Standard Time"),
+    ("Asia/Katmandu", "Nepal Standard Time"),
+    ("Asia/Kathmandu", "Nepal Standard Time"),
+    ("Asia/Bishkek", "Central Asia Standard Time"),
+    ("Asia/Dhaka", "Bangladesh Standard Time"),
+    ("Asia/Omsk", "Omsk Standard Time"),
+    ("Asia/Rangoon", "Myanmar Standard Time"),
+    ("Asia/Yangon", "Myanmar Standard Time"),
+    ("Asia/Bangkok", "SE Asia Standard Time"),
+    ("Asia/Jakarta", "SE Asia Standard Time"),
+    ("Asia/Ho_Chi_Minh", "SE Asia Standard Time"),
+    ("Asia/Barnaul", "Altai Standard Time"),
+    ("Asia/Hovd", "W. Mongolia Standard Time"),
+    ("Asia/Krasnoyarsk", "North Asia Standard Time"),
+    ("Asia/Novosibirsk", "N. Central Asia Standard Time"),
+    ("Asia/Tomsk", "Tomsk Standard Time"),
+    ("Asia/Shanghai", "China Standard Time"),
+    ("Asia/Hong_Kong", "China Standard Time"),
+    ("Asia/Irkutsk", "North Asia East Standard Time"),
+    ("Asia/Singapore", "Singapore Standard Time"),
+    ("Asia/Kuala_Lumpur", "Singapore Standard Time"),
+    ("Asia/Manila", "Singapore Standard Time"),
+    ("Australia/Perth", "W. Australia Standard Time"),
+    ("Asia/Taipei", "Taipei Standard Time"),
+    ("Asia/Ulaanbaatar", "Ulaanbaatar Standard Time"),
+    ("Australia/Eucla", "Aus Central W. Standard Time"),
+    ("Asia/Chita", "Transbaikal Standard Time"),
+    ("Asia/Tokyo", "Tokyo Standard Time"),
+    ("Asia/Pyongyang", "North Korea Standard Time"),
+    ("Asia/Seoul", "Korea Standard Time"),
+    ("Asia/Yakutsk", "Yakutsk Standard Time"),
+    ("Australia/Adelaide", "Cen. Australia Standard Time"),
+    ("Australia/Darwin", "AUS Central Standard Time"),
+    ("Australia/Brisbane", "E. Australia Standard Time"),
+    ("Australia/Sydney", "AUS Eastern Standard Time"),
+    ("Australia/Melbourne", "AUS Eastern Standard Time"),
+    ("Australia/Canberra", "AUS Eastern Standard Time"),
+    ("Pacific/Port_Moresby", "West Pacific Standard Time"),
+    ("Australia/Hobart", "Tasmania Standard Time"),
+    ("Asia/Vladivostok", "Vladivostok Standard Time"),
+    ("Australia/Lord_Howe", "Lord Howe Standard Time"),
+    ("Pacific/Bougainville", "Bougainville Standard Time"),
+    ("Asia/Srednekolymsk", "Russia Time Zone 10"),
+    ("Asia/Magadan", "Magadan Standard Time"),
+    ("Pacific/Norfolk", "Norfolk Standard Time"),
+    ("Asia/Sakhalin", "Sakhalin Standard Time"),
+    ("Pacific/Guadalcanal", "Central Pacific Standard Time"),
+    ("Asia/Kamchatka", "Russia Time Zone 11"),
+    ("Pacific/Auckland", "New Zealand Standard Time"),
+    ("Etc/GMT-12", "UTC+12"),
+    ("Pacific/Fiji", "Fiji Standard Time"),
+    ("Pacific/Chatham", "Chatham Islands Standard Time"),
+    ("Etc/GMT-13", "UTC+13"),
+    ("Pacific/Tongatapu", "Tonga Standard Time"),
+    ("Pacific/Apia", "Samoa Standard Time"),
+    ("Pacific/Kiritimati", "Line Islands Standard Time"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Zone {
+    Local,
+    /// A fixed offset from UTC, in minutes.
+    Fixed(i32),
+    Windows(Box<DYNAMIC_TIME_ZONE_INFORMATION>),
+}
+
+/// A time zone: the system's local zone, a fixed UTC offset, or a Windows
+/// time zone with its daylight saving rules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeZone(Zone);
+
+/// Parse `UTC`, `UTC+5`, `GMT-08:00`, `+0530` and the like into minutes
+/// east of UTC. Offsets beyond ±14 hours are rejected.
+fn parse_utc_offset(input: &str) -> Option<i32> {
+    let upper = input.trim().to_ascii_uppercase();
+    if upper == "Z" {
+        return Some(0);
+    }
+    let prefixed = ["UTC", "GMT"]
+        .iter()
+        .find_map(|prefix| upper.strip_prefix(prefix));
+    let rest = prefixed.unwrap_or(&upper).trim();
+    if rest.is_empty() {
+        // Plain `UTC` or `GMT`.
+        return prefixed.map(|_| 0);
+    }
+    let (sign, digits) = match rest.as_bytes()[0] {
+        b'+' => (1, &rest[1..]),
+        b'-' => (-1, &rest[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((h, m)) if m.len() == 2 => (h, m),
+        Some(_) => return None,
+        None if digits.len() == 4 => digits.split_at(2),
+        None if (1..=2).contains(&digits.len()) => (digits, "0"),
+        None => return None,
+    };
+    // `parse` alone would also accept a second sign.
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !(is_number(hours) && is_number(minutes)) {
+        return None;
+    }
+    let (hours, minutes): (i32, i32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    let total = hours * 60 + minutes;
+    (minutes < 60 && total <= 14 * 60).then_some(sign * total)
+}
+
+/// The Windows time zone ID for an IANA name such as `Europe/Berlin`,
+/// ignoring case.
+fn windows_id_for_iana(name: &str) -> Option<&'static str> {
+    let name = name.trim();
+    IANA_ZONES
+        .iter()
+        .find(|(iana, _)| iana.eq_ignore_ascii_case(name))
+        .map(|&(_, windows)| windows)
+}
+
+/// Find the Windows time zone whose ID (e.g. `Eastern Standard Time`)
+/// matches `name`, ignoring case.
+fn find_windows_zone(name: &str) -> Option<DYNAMIC_TIME_ZONE_INFORMATION> {
+    let name = name.trim();
+    for index in 0.. {
+        let mut info = DYNAMIC_TIME_ZONE_INFORMATION::default();
+        match unsafe { EnumDynamicTimeZoneInformation(index, &mut info) } {
+            0 => {}
+            ERROR_NO_MORE_ITEMS => break,
+            // Skip entries that fail to load rather than giving up.
+            _ => continue,
+        }
+        let len = info
+            .TimeZoneKeyName
+            .iter()
+            .position(|&unit| unit == 0)
+            .unwrap_or(info.TimeZoneKeyName.len());
+        if String::from_utf16_lossy(&info.TimeZoneKeyName[..len]).eq_ignore_ascii_case(name) {
+            return Some(info);
+        }
+    }
+    None
+}
+
+impl TimeZone {
+    /// The system's local time zone.
+    pub fn local() -> Self {
+        Self(Zone::Local)
+    }
+
+    /// A fixed offset east of UTC, in minutes (e.g. `330` for UTC+5:30).
+    pub fn utc_offset(minutes: i32) -> Self {
+        Self(Zone::Fixed(minutes))
+    }
+
+    /// Parse `Local`, a UTC offset (`UTC`, `UTC+2`, `GMT-08:00`, `+0530`)
+    /// or a Windows time zone ID such as `Eastern Standard Time` or
+    /// `W. Europe Standard Time` (as listed by `tzutil /l`). Windows zones
+    /// follow daylight saving time; fixed offsets don't.
+    ///
+    /// Common IANA names like `Europe/Berlin` or `America/New_York` are
+    /// accepted too and give the matching Windows zone, which has the same
+    /// current rules (though not necessarily the same history).
+    pub fn parse(input: &str) -> Option<Self> {
+        if input.trim().eq_ignore_ascii_case("local") {
+            return Some(Self::local());
+        }
+        if let Some(minutes) = parse_utc_offset(input) {
+            return Some(Self::utc_offset(minutes));
+        }
+        find_windows_zone(input)
+            .or_else(|| windows_id_for_iana(input).and_then(find_windows_zone))
+            .map(|info| Self(Zone::Windows(Box::new(info))))
+    }
+}
+
+/// The current wall-clock time in `tz` as `(hour, minute, second)`, with
+/// the hour in `0..24`.
+pub fn now_in(tz: &TimeZone) -> (u32, u32, u32) {
+    match &tz.0 {
+        Zone::Local => {
+            let now = unsafe { GetLocalTime() };
+            (now.wHour.into(), now.wMinute.into(), now.wSecond.into())
+        }
+        Zone::Fixed(minutes) => {
+            let utc = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs() as i64);
+            let secs = (utc + *minutes as i64 * 60).rem_euclid(86_400) as u32;
+            (secs / 3_600, secs / 60 % 60, secs % 60)
+        }
+        Zone::Windows(info) => {
+            let utc = unsafe { GetSystemTime() };
+            let mut local = utc;
+            // Only fails for invalid zone data; fall back to UTC then.
+            let _ = unsafe { SystemTimeToTzSpecificLocalTimeEx(Some(&**info), &utc, &mut local) };
+            (
+                local.wHour.into(),
+                local.wMinute.into(),
+                local.wSecond.into(),
+            )
+        }
+    }
+}
+
+impl RainmeterContext {
+    /// Read a time zone option such as `TimeZone=UTC+5:30` or
+    /// `TimeZone=Tokyo Standard Time` (see [`TimeZone::parse`]). Returns
+    /// `None` if the option is missing or unknown; the latter is logged.
+    pub fn read_timezone(&self, key: &str) -> Option<TimeZone> {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return None;
+        }
+        let tz = TimeZone::parse(&value);
+        if tz.is_none() {
+            let hint = if value.contains('/') {
+                "; IANA names are only mapped for common zones, so use a Windows \
+                 time zone ID (see `tzutil /l`) or a UTC offset instead"
+            } else {
+                ""
+            };
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: unknown time zone '{}'{}", key, value, hint),
+            );
+        }
+        tz
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    fn zone_id(tz: &TimeZone) -> String {
+        match &tz.0 {
+            Zone::Windows(info) => {
+                let len = info.TimeZoneKeyName.iter().position(|&u| u == 0).unwrap();
+                String::from_utf16_lossy(&info.TimeZoneKeyName[..len])
+            }
+            other => panic!("expected a Windows zone, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_utc_offsets() {
+        assert_eq!(parse_utc_offset("UTC"), Some(0));
+        assert_eq!(parse_utc_offset("z"), Some(0));
+        assert_eq!(parse_utc_offset("UTC+5"), Some(300));
+        assert_eq!(parse_utc_offset("utc+5:30"), Some(330));
+        assert_eq!(parse_utc_offset("GMT-08:00"), Some(-480));
+        assert_eq!(parse_utc_offset("+0530"), Some(330));
+        assert_eq!(parse_utc_offset("UTC+14"), Some(840));
+
+        assert_eq!(parse_utc_offset("UTC+15"), None);
+        assert_eq!(parse_utc_offset("UTC+5:7"), None);
+        assert_eq!(parse_utc_offset("UTC+-5"), None);
+        assert_eq!(parse_utc_offset("5"), None);
+        assert_eq!(parse_utc_offset("Berlin"), None);
+    }
+
+    #[test]
+    fn parses_named_zones() {
+        assert_eq!(TimeZone::parse(" local "), Some(TimeZone::local()));
+        assert_eq!(TimeZone::parse("UTC-3"), Some(TimeZone::utc_offset(-180)));
+
+        let berlin = TimeZone::parse("w. europe standard time").unwrap();
+        assert_eq!(zone_id(&berlin), "W. Europe Standard Time");
+        assert_eq!(TimeZone::parse("Atlantis Standard Time"), None);
+    }
+
+    #[test]
+    fn maps_iana_names_to_windows_zones() {
+        let berlin = TimeZone::parse("Europe/Berlin").unwrap();
+        assert_eq!(zone_id(&berlin), "W. Europe Standard Time");
+        assert_eq!(TimeZone::parse("europe/rome"), Some(berlin));
+        let new_york = TimeZone::parse("America/New_York").unwrap();
+        assert_eq!(zone_id(&new_york), "Eastern Standard Time");
+        assert_eq!(TimeZone::parse("Mars/Olympus_Mons"), None);
+    }
+
+    #[test]
+    fn every_iana_name_is_unique() {
+        for (i, (name, _)) in IANA_ZONES.iter().enumerate() {
+            assert!(
+                !IANA_ZONES[..i]
+                    .iter()
+                    .any(|(other, _)| other.eq_ignore_ascii_case(name)),
+                "{} is listed twice",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn fixed_offsets_give_wall_clock_time() {
+        let (utc_h, utc_m, _) = now_in(&TimeZone::utc_offset(0));
+        let (h, m, s) = now_in(&TimeZone::utc_offset(90));
+        assert!(h < 24 && m < 60 && s < 60);
+        let shift = (h * 60 + m + 24 * 60 - (utc_h * 60 + utc_m)) % (24 * 60);
+        // Allow for the minute ticking over between the two reads.
+        assert!((90..=91).contains(&shift), "{}", shift);
+    }
+
+    #[test]
+    fn unknown_iana_names_are_explained() {
+        let rm = MockContext::new();
+        rm.set("TimeZone", "Mars/Olympus_Mons");
+        assert_eq!(rm.read_timezone("TimeZone"), None);
+        let warnings = rm.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("tzutil /l"), "{}", warnings[0]);
+
+        rm.set("TimeZone", "Europe/Berlin");
+        assert!(rm.read_timezone("TimeZone").is_some());
+        rm.unset("TimeZone");
+        assert_eq!(rm.read_timezone("TimeZone"), None);
+        assert_eq!(rm.warnings().len(), 1);
+    }
+}
@@ -0,0 +1,462 @@
+//! Durations in options and wall-clock scheduling independent of `update`.
+
+use crate::{RainmeterContext, RmLogLevel};
+use std::time::{Duration, Instant};
+
+/// Parse a duration like `500ms`, `30s`, `5m`, `2h`, `1d` or combinations
+/// such as `1h30m`. Units are case-insensitive and amounts may be fractional
+/// (`1.5h`); a bare number means seconds. Returns `None` for anything else.
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    if let Ok(secs) = input.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+
+    let mut total = 0.0;
+    let mut rest = input;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let amount: f64 = rest[..number_len].parse().ok()?;
+        rest = rest[number_len..].trim_start();
+
+        let unit_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let seconds_per_unit = match rest[..unit_len].to_ascii_lowercase().as_str() {
+            "ms" => 0.001,
+            "s" => 1.0,
+            "m" => 60.0,
+            "h" => 3_600.0,
+            "d" => 86_400.0,
+            _ => return None,
+        };
+        total += amount * seconds_per_unit;
+        rest = rest[unit_len..].trim_start();
+    }
+    Duration::try_from_secs_f64(total).ok()
+}
+
+impl RainmeterContext {
+    /// Read a duration option (see [`parse_duration`]). Invalid values are
+    /// logged and fall back to `default`.
+    pub fn read_duration(&self, key: &str, default: Duration) -> Duration {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return default;
+        }
+        parse_duration(&value).unwrap_or_else(|| {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: invalid duration '{}'", key, value),
+            );
+            default
+        })
+    }
+
+    /// Read a relative time offset such as `Since=5m`, `2h ago` or
+    /// `1h30m ago`, for "show items newer than" style options. The value
+    /// uses the [`parse_duration`] syntax, optionally followed by `ago`; this
+    /// is deliberately much simpler than natural-language parsing. Subtract
+    /// the result from `SystemTime::now()` to get the point in time.
+    ///
+    /// Returns `None` if the option is missing or can't be parsed; the
+    /// latter is logged.
+    pub fn read_relative_time(&self, key: &str) -> Option<Duration> {
+        let value = self.read_string(key, "");
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+        let lower = trimmed.to_ascii_lowercase();
+        let offset = lower.strip_suffix("ago").unwrap_or(&lower);
+        let parsed = parse_duration(offset);
+        if parsed.is_none() {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: invalid relative time '{}'", key, value),
+            );
+        }
+        parsed
+    }
+
+    /// Read a `|`-separated list of `name:duration` timers, e.g.
+    /// `Timers=fetch:30s | rotate:5m` (see [`parse_duration`] for the
+    /// duration syntax). Malformed entries are logged and skipped.
+    pub fn read_timer_set(&self, key: &str) -> TimerSet {
+        let mut timers = TimerSet::new();
+        let entries = self.read_list_schema(key, '|', |entry| {
+            let (name, interval) = entry.split_once(':')?;
+            let name = name.trim();
+            (!name.is_empty()).then_some((name.to_string(), parse_duration(interval)?))
+        });
+        for (name, interval) in entries {
+            timers.insert(&name, interval);
+        }
+        timers
+    }
+}
+
+/// Fires at a fixed wall-clock interval, no matter how often it's polled.
+///
+/// Rainmeter's `UpdateDivider` controls how often `update` runs, which is
+/// really about how often the skin *redraws*. Data sources (HTTP APIs, slow
+/// system queries) usually want their own, much slower schedule. By
+/// convention that's configured with a `RefreshInterval=` option, which
+/// [`from_refresh_interval`](Self::from_refresh_interval) reads:
+///
+/// ```rust,ignore
+/// fn reload(&mut self, rm: RainmeterContext, _max: &mut f64) {
+///     self.refresh = WallClockTimer::from_refresh_interval(&rm, Duration::from_secs(300));
+/// }
+///
+/// fn update(&mut self, _rm: RainmeterContext) -> f64 {
+///     if self.refresh.should_refresh(Instant::now()) {
+///         self.value = self.fetch();
+///     }
+///     self.value
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WallClockTimer {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl WallClockTimer {
+    /// A timer that fires on its first poll and then every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last: None,
+        }
+    }
+
+    /// A timer using the measure's `RefreshInterval=` option.
+    pub fn from_refresh_interval(rm: &RainmeterContext, default: Duration) -> Self {
+        Self::new(rm.read_duration("RefreshInterval", default))
+    }
+
+    /// The configured interval.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Returns `true` (and restarts the interval) if `interval` has elapsed
+    /// since the last time this returned `true`, or on the first call.
+    pub fn should_refresh(&mut self, now: Instant) -> bool {
+        match self.last {
+            Some(last) if now.saturating_duration_since(last) < self.interval => false,
+            _ => {
+                self.last = Some(now);
+                true
+            }
+        }
+    }
+
+    /// Make the next `should_refresh` fire immediately.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+/// Several named [`WallClockTimer`]s, for plugins with more than one
+/// periodic task. Read them with
+/// [`read_timer_set`](RainmeterContext::read_timer_set):
+///
+/// ```rust,ignore
+/// // Timers=fetch:30s | rotate:5m
+/// fn update(&mut self, _rm: RainmeterContext) -> f64 {
+///     let now = Instant::now();
+///     if self.timers.fired("fetch", now) {
+///         self.fetch();
+///     }
+///     if self.timers.fired("rotate", now) {
+///         self.rotate();
+///     }
+///     self.value
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TimerSet {
+    timers: Vec<(String, WallClockTimer)>,
+}
+
+impl TimerSet {
+    /// An empty set; add timers with [`insert`](Self::insert).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a timer, replacing any existing one with the same name.
+    pub fn insert(&mut self, name: &str, interval: Duration) {
+        let timer = WallClockTimer::new(interval);
+        match self.find(name) {
+            Some(index) => self.timers[index].1 = timer,
+            None => self.timers.push((name.to_string(), timer)),
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.timers
+            .iter()
+            .position(|(existing, _)| existing.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether the timer called `name` (ignoring case) is due, as with
+    /// [`WallClockTimer::should_refresh`]. Unknown names never fire.
+    pub fn fired(&mut self, name: &str, now: Instant) -> bool {
+        match self.find(name) {
+            Some(index) => self.timers[index].1.should_refresh(now),
+            None => false,
+        }
+    }
+
+    /// The interval of the timer called `name`.
+    pub fn interval(&self, name: &str) -> Option<Duration> {
+        self.find(name).map(|index| self.timers[index].1.interval())
+    }
+
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+}
+
+/// Caps how long a single `update` spends on batch work, so a long job is
+/// spread over several updates instead of stalling the skin.
+///
+/// Call [`start`](Self::start) at the top of `update`, then process items
+/// while [`can_continue`](Self::can_continue) holds; whatever is left stays
+/// queued for the next update:
+///
+/// ```rust,ignore
+/// fn update(&mut self, _rm: RainmeterContext) -> f64 {
+///     self.budget.start();
+///     while self.budget.can_continue() {
+///         let Some(item) = self.pending.pop_front() else { break };
+///         self.process(item);
+///     }
+///     self.pending.len() as f64
+/// }
+/// ```
+///
+/// The check happens between items, so one slow item can still overrun the
+/// budget; keep items small.
+#[derive(Debug, Clone)]
+pub struct TimeBudget {
+    budget: Duration,
+    started: Instant,
+}
+
+impl TimeBudget {
+    /// A budget of `budget` per update, e.g. `Duration::from_millis(8)`.
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            budget,
+            started: Instant::now(),
+        }
+    }
+
+    /// The configured budget.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Start this update's time slice.
+    pub fn start(&mut self) {
+        self.start_at(Instant::now());
+    }
+
+    /// [`start`](Self::start) with an explicit clock reading.
+    pub fn start_at(&mut self, now: Instant) {
+        self.started = now;
+    }
+
+    /// Whether there's time left in the slice started by the last
+    /// [`start`](Self::start).
+    pub fn can_continue(&self) -> bool {
+        self.can_continue_at(Instant::now())
+    }
+
+    /// [`can_continue`](Self::can_continue) with an explicit clock reading.
+    pub fn can_continue_at(&self, now: Instant) -> bool {
+        self.remaining_at(now) > Duration::ZERO
+    }
+
+    /// Time left in the current slice (zero once it's used up).
+    pub fn remaining(&self) -> Duration {
+        self.remaining_at(Instant::now())
+    }
+
+    /// [`remaining`](Self::remaining) with an explicit clock reading.
+    pub fn remaining_at(&self, now: Instant) -> Duration {
+        self.budget
+            .saturating_sub(now.saturating_duration_since(self.started))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("500ms"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_duration("1.5h"), Some(Duration::from_secs(5_400)));
+        assert_eq!(parse_duration(" 90 "), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1H 30m"), Some(Duration::from_secs(5_400)));
+        // A trailing amount needs a unit.
+        assert_eq!(parse_duration("1h30"), None);
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("soon"), None);
+    }
+
+    #[test]
+    fn timer_fires_once_per_interval() {
+        let base = Instant::now();
+        let mut timer = WallClockTimer::new(Duration::from_secs(10));
+        assert!(timer.should_refresh(base));
+        // Polling more often than the interval doesn't fire again.
+        for secs in 1..10 {
+            assert!(!timer.should_refresh(base + Duration::from_secs(secs)));
+        }
+        assert!(timer.should_refresh(base + Duration::from_secs(10)));
+        assert!(!timer.should_refresh(base + Duration::from_secs(11)));
+
+        timer.reset();
+        assert!(timer.should_refresh(base + Duration::from_secs(12)));
+    }
+
+    #[test]
+    fn reads_refresh_interval() {
+        let rm = MockContext::new();
+        let default = Duration::from_secs(300);
+        let timer = WallClockTimer::from_refresh_interval(&rm.context(), default);
+        assert_eq!(timer.interval(), default);
+
+        rm.set("RefreshInterval", "2m");
+        let timer = WallClockTimer::from_refresh_interval(&rm.context(), default);
+        assert_eq!(timer.interval(), Duration::from_secs(120));
+        rm.set("RefreshInterval", "45s");
+        let timer = WallClockTimer::from_refresh_interval(&rm.context(), default);
+        assert_eq!(timer.interval(), Duration::from_secs(45));
+        assert!(rm.warnings().is_empty());
+
+        rm.set("RefreshInterval", "often");
+        let timer = WallClockTimer::from_refresh_interval(&rm.context(), default);
+        assert_eq!(timer.interval(), default);
+        assert_eq!(rm.warnings().len(), 1);
+    }
+
+    #[test]
+    fn reads_relative_times() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_relative_time("Since"), None);
+        for (value, secs) in [
+            ("5m", 300),
+            ("2h", 7_200),
+            ("1d", 86_400),
+            ("30s", 30),
+            ("1h30m", 5_400),
+            ("2h ago", 7_200),
+            ("1h30m AGO", 5_400),
+        ] {
+            rm.set("Since", value);
+            assert_eq!(
+                rm.read_relative_time("Since"),
+                Some(Duration::from_secs(secs)),
+                "{}",
+                value
+            );
+        }
+        assert!(rm.warnings().is_empty());
+
+        rm.set("Since", "last tuesday");
+        assert_eq!(rm.read_relative_time("Since"), None);
+        assert_eq!(
+            rm.warnings(),
+            ["Since: invalid relative time 'last tuesday'".to_string()]
+        );
+    }
+
+    #[test]
+    fn budget_spreads_a_queue_over_several_slices() {
+        let mut budget = TimeBudget::new(Duration::from_millis(8));
+        let mut pending: std::collections::VecDeque<u32> = (0..10).collect();
+        let mut done = Vec::new();
+        let mut slices = Vec::new();
+        let mut now = Instant::now();
+        while !pending.is_empty() {
+            budget.start_at(now);
+            let mut processed = 0;
+            while budget.can_continue_at(now) {
+                let Some(item) = pending.pop_front() else {
+                    break;
+                };
+                done.push(item);
+                processed += 1;
+                // Each item takes 3ms.
+                now += Duration::from_millis(3);
+            }
+            slices.push(processed);
+            // The rest of the update and the wait for the next one.
+            now += Duration::from_secs(1);
+        }
+        assert_eq!(done, (0..10).collect::<Vec<_>>());
+        // 0, 3 and 6ms are within the budget; 9ms isn't.
+        assert_eq!(slices, [3, 3, 3, 1]);
+    }
+
+    #[test]
+    fn budget_remaining_counts_down() {
+        let mut budget = TimeBudget::new(Duration::from_millis(8));
+        let base = Instant::now();
+        budget.start_at(base);
+        assert_eq!(budget.remaining_at(base), Duration::from_millis(8));
+        assert_eq!(
+            budget.remaining_at(base + Duration::from_millis(5)),
+            Duration::from_millis(3)
+        );
+        assert!(!budget.can_continue_at(base + Duration::from_millis(8)));
+        assert_eq!(
+            budget.remaining_at(base + Duration::from_millis(20)),
+            Duration::ZERO
+        );
+        // A clock reading from before the slice started counts as no time used.
+        assert!(budget.can_continue_at(base - Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn timer_set_fires_each_timer_on_its_own_schedule() {
+        let rm = MockContext::new();
+        rm.set(
+            "Timers",
+            "fetch:30s | rotate:5m | broken | :10s | slow:soon",
+        );
+        let mut timers = rm.read_timer_set("Timers");
+        assert_eq!(timers.len(), 2);
+        assert_eq!(timers.interval("FETCH"), Some(Duration::from_secs(30)));
+        assert_eq!(timers.interval("rotate"), Some(Duration::from_secs(300)));
+        assert_eq!(rm.warnings().len(), 3);
+
+        let base = Instant::now();
+        let mut fired = (0, 0);
+        for secs in (0..=600).step_by(10) {
+            let now = base + Duration::from_secs(secs);
+            fired.0 += timers.fired("Fetch", now) as u32;
+            fired.1 += timers.fired("ROTATE", now) as u32;
+        }
+        // At 0s and then every 30s / 5m up to 600s.
+        assert_eq!(fired, (21, 3));
+        assert!(!timers.fired("missing", base));
+    }
+}
@@ -0,0 +1,81 @@
+//! Opt-in tracing of the FFI calls `RainmeterContext` makes on a measure's
+//! behalf, for debugging this crate. Set the hidden option `__RmRsTrace=1` on
+//! a measure to log every call (name and arguments) at debug level.
+//!
+//! When no measure has tracing enabled, the cost per call is a single relaxed
+//! atomic load; the trace message is never formatted.
+
+use std::ffi::c_void;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The hidden option that enables tracing on a measure.
+pub(crate) const TRACE_OPTION: &str = "__RmRsTrace";
+
+/// Fast path: `false` whenever `TRACED` is empty.
+static ANY_TRACED: AtomicBool = AtomicBool::new(false);
+/// `rm` pointers of the measures with tracing enabled.
+static TRACED: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+pub(crate) fn set_traced(raw: *mut c_void, enabled: bool) {
+    let mut traced = TRACED.lock().unwrap_or_else(|e| e.into_inner());
+    traced.retain(|&r| r != raw as usize);
+    if enabled {
+        traced.push(raw as usize);
+    }
+    ANY_TRACED.store(!traced.is_empty(), Ordering::Relaxed);
+}
+
+#[inline]
+pub(crate) fn is_traced(raw: *mut c_void) -> bool {
+    ANY_TRACED.load(Ordering::Relaxed)
+        && TRACED
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(&(raw as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    fn trace_logs(rm: &MockContext) -> Vec<String> {
+        rm.logs()
+            .into_iter()
+            .map(|(_, message)| message)
+            .filter(|message| message.starts_with("[trace] "))
+            .collect()
+    }
+
+    #[test]
+    fn traces_calls_only_while_enabled() {
+        let rm = MockContext::new();
+        rm.set("Value", "7");
+
+        rm.read_string("Value", "");
+        assert!(trace_logs(&rm).is_empty());
+
+        rm.set(TRACE_OPTION, "1");
+        rm.refresh_trace();
+        rm.read_string("Value", "");
+        assert_eq!(trace_logs(&rm), [r#"[trace] RmReadString("Value", "", 1)"#]);
+        assert_eq!(rm.logs()[0].0, 4);
+
+        rm.set(TRACE_OPTION, "0");
+        rm.refresh_trace();
+        rm.clear_logs();
+        rm.read_string("Value", "");
+        assert!(trace_logs(&rm).is_empty());
+    }
+
+    #[test]
+    fn finalize_stops_tracing() {
+        let rm = MockContext::new();
+        rm.set(TRACE_OPTION, "1");
+        rm.refresh_trace();
+        assert!(is_traced(rm.raw));
+        rm.clear_trace();
+        assert!(!is_traced(rm.raw));
+    }
+}
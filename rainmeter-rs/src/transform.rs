@@ -0,0 +1,188 @@
+use crate::RainmeterContext;
+use crate::format::MAX_PRECISION;
+use std::ops::Deref;
+
+/// Apply a linear calibration to a raw reading: `v * scale + offset`.
+///
+/// ```rust
+/// # use rainmeter::apply_transform;
+/// // Raw sensor value in tenths of a degree, biased by 40.
+/// assert_eq!(apply_transform(650.0, 0.1, -40.0), 25.0);
+/// ```
+pub fn apply_transform(v: f64, scale: f64, offset: f64) -> f64 {
+    v * scale + offset
+}
+
+/// One step of a [`Pipeline`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stage {
+    /// `scale:x` multiplies by `x`.
+    Scale(f64),
+    /// `offset:x` adds `x`.
+    Offset(f64),
+    /// `clamp:min,max` limits the value to `[min, max]`.
+    Clamp(f64, f64),
+    /// `round` rounds to a whole number, `round:n` to `n` decimal places.
+    Round(u32),
+    /// `abs` drops the sign.
+    Abs,
+}
+
+impl Stage {
+    /// Parse one `name[:args]` stage; names ignore case.
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (name, args) = entry.split_once(':').unwrap_or((entry, ""));
+        let args = args.trim();
+        let number = |text: &str| text.trim().parse::<f64>().ok().filter(|v| v.is_finite());
+        Some(match name.trim().to_ascii_lowercase().as_str() {
+            "scale" => Stage::Scale(number(args)?),
+            "offset" => Stage::Offset(number(args)?),
+            "clamp" => {
+                let (min, max) = args.split_once(',')?;
+                let (min, max) = (number(min)?, number(max)?);
+                if min > max {
+                    return None;
+                }
+                Stage::Clamp(min, max)
+            }
+            "round" if args.is_empty() => Stage::Round(0),
+            "round" => Stage::Round(
+                args.parse()
+                    .ok()
+                    .filter(|&digits| digits as usize <= MAX_PRECISION)?,
+            ),
+            "abs" if args.is_empty() => Stage::Abs,
+            _ => return None,
+        })
+    }
+
+    pub fn apply(self, v: f64) -> f64 {
+        match self {
+            Stage::Scale(factor) => v * factor,
+            Stage::Offset(offset) => v + offset,
+            Stage::Clamp(min, max) => v.clamp(min, max),
+            Stage::Round(digits) => {
+                let factor = 10f64.powi(digits as i32);
+                (v * factor).round() / factor
+            }
+            Stage::Abs => v.abs(),
+        }
+    }
+}
+
+/// An ordered list of [`Stage`]s applied one after another, e.g.
+/// `scale:0.1 | offset:-40 | clamp:0,100`.
+///
+/// Derefs to the underlying `Vec`. An empty pipeline passes values through.
+///
+/// ```rust
+/// # use rainmeter::{Pipeline, Stage};
+/// let pipeline = Pipeline::new(vec![Stage::Scale(0.1), Stage::Offset(-40.0), Stage::Clamp(0.0, 100.0)]);
+/// assert_eq!(pipeline.apply(650.0), 25.0);
+/// assert_eq!(pipeline.apply(100.0), 0.0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pipeline {
+    stages: Vec<Stage>,
+}
+
+impl Pipeline {
+    pub fn new(stages: Vec<Stage>) -> Self {
+        Self { stages }
+    }
+
+    /// Run `v` through every stage in order.
+    pub fn apply(&self, v: f64) -> f64 {
+        self.stages.iter().fold(v, |v, stage| stage.apply(v))
+    }
+}
+
+impl Deref for Pipeline {
+    type Target = Vec<Stage>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.stages
+    }
+}
+
+impl RainmeterContext {
+    /// Read a `(scale, offset)` pair for [`apply_transform`], e.g.
+    /// `Scale=0.1` and `Offset=-40`. Missing options give the identity
+    /// transform `(1.0, 0.0)`.
+    pub fn read_transform(&self, scale_key: &str, offset_key: &str) -> (f64, f64) {
+        (
+            self.read_formula(scale_key, 1.0),
+            self.read_formula(offset_key, 0.0),
+        )
+    }
+
+    /// Read a `|`-separated value pipeline, such as
+    /// `Pipeline=scale:0.1 | offset:-40 | clamp:0,100 | round:1`. See
+    /// [`Stage`] for the stages. Malformed stages are logged and skipped.
+    pub fn read_pipeline(&self, key: &str) -> Pipeline {
+        Pipeline::new(self.read_list_schema(key, '|', Stage::parse))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn reads_transforms() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_transform("Scale", "Offset"), (1.0, 0.0));
+        rm.set("Scale", "0.1");
+        assert_eq!(rm.read_transform("Scale", "Offset"), (0.1, 0.0));
+        rm.unset("Scale").set("Offset", "-40");
+        assert_eq!(rm.read_transform("Scale", "Offset"), (1.0, -40.0));
+    }
+
+    #[test]
+    fn reads_pipelines() {
+        let rm = MockContext::new();
+        rm.set(
+            "Pipeline",
+            "scale:0.1 | offset:-40 | clamp:0,100 | round:1 | ABS",
+        );
+        let pipeline = rm.read_pipeline("Pipeline");
+        assert_eq!(
+            pipeline[..],
+            [
+                Stage::Scale(0.1),
+                Stage::Offset(-40.0),
+                Stage::Clamp(0.0, 100.0),
+                Stage::Round(1),
+                Stage::Abs,
+            ]
+        );
+        assert_eq!(pipeline.apply(652.34), 25.2);
+        assert_eq!(pipeline.apply(100.0), 0.0);
+        assert_eq!(pipeline.apply(9000.0), 100.0);
+        assert!(rm.warnings().is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_stages() {
+        for entry in [
+            "clamp:5,1",
+            "clamp:5",
+            "round:99",
+            "round:-1",
+            "scale",
+            "scale:x",
+            "abs:1",
+            "log",
+        ] {
+            assert_eq!(Stage::parse(entry), None, "{}", entry);
+        }
+        assert_eq!(Stage::parse("round"), Some(Stage::Round(0)));
+        assert_eq!(Stage::parse("round:15"), Some(Stage::Round(15)));
+
+        let rm = MockContext::new();
+        rm.set("Pipeline", "clamp:5,1 | round:99 | scale | offset:2");
+        assert_eq!(rm.read_pipeline("Pipeline")[..], [Stage::Offset(2.0)]);
+        assert_eq!(rm.warnings().len(), 3);
+    }
+}
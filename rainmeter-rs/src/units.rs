@@ -0,0 +1,237 @@
+//! Option readers for quantities with units.
+
+use crate::{RainmeterContext, RmLogLevel, format_value};
+
+/// SI prefixes used by [`format_si`], smallest first, with their exponent of
+/// 1000.
+const SI_PREFIXES: &[(&str, i32)] = &[
+    ("p", -4),
+    ("n", -3),
+    ("\u{b5}", -2),
+    ("m", -1),
+    ("", 0),
+    ("k", 1),
+    ("M", 2),
+    ("G", 3),
+    ("T", 4),
+    ("P", 5),
+];
+
+/// Parse a number with an optional SI suffix, like `1.5k` or `2M`.
+fn parse_si_number(input: &str) -> Option<f64> {
+    let input = input.trim();
+    let number_len = input
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')))
+        .unwrap_or(input.len());
+    let (number, suffix) = input.split_at(number_len);
+    let exponent = match suffix.trim() {
+        "K" => 1,
+        // `u` is the ASCII stand-in for `µ`.
+        "u" => -2,
+        suffix => SI_PREFIXES.iter().find(|(prefix, _)| *prefix == suffix)?.1,
+    };
+    let value: f64 = number.parse().ok()?;
+    Some(value * 1000f64.powi(exponent)).filter(|v| v.is_finite())
+}
+
+/// Format `value` with an SI prefix and `precision` decimal places, picking
+/// the prefix that leaves between 1 and 1000 in front of it.
+///
+/// ```rust
+/// # use rainmeter::format_si;
+/// assert_eq!(format_si(1500.0, 1), "1.5k");
+/// assert_eq!(format_si(2_000_000.0, 0), "2M");
+/// assert_eq!(format_si(0.25, 0), "250m");
+/// ```
+pub fn format_si(value: f64, precision: usize) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return format_value(value, precision, false);
+    }
+    let smallest = SI_PREFIXES.first().map_or(0, |p| p.1);
+    let largest = SI_PREFIXES.last().map_or(0, |p| p.1);
+    let mut exponent = ((value.abs().log10() / 3.0).floor() as i32).clamp(smallest, largest);
+    // Rounding can carry into the next prefix, e.g. 999.96 -> "1000.0".
+    let rounded = |exponent: i32| {
+        let scaled = value / 1000f64.powi(exponent);
+        format_value(scaled, precision, false)
+    };
+    let mut text = rounded(exponent);
+    if exponent < largest && text.trim_start_matches('-').starts_with("1000") {
+        exponent += 1;
+        text = rounded(exponent);
+    }
+    let prefix = SI_PREFIXES
+        .iter()
+        .find(|(_, e)| *e == exponent)
+        .map_or("", |p| p.0);
+    format!("{}{}", text, prefix)
+}
+
+/// Parse a data rate like `10Mbps`, `1.5 Gb/s` or `20MB/s` into bits per
+/// second. See [`RainmeterContext::read_bit_rate`] for the accepted units.
+fn parse_bit_rate(input: &str) -> Option<u64> {
+    let input = input.trim();
+    let number_len = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(input.len());
+    let amount: f64 = input[..number_len].parse().ok()?;
+    let unit = input[number_len..].trim();
+    if unit.is_empty() {
+        return Some(amount.round() as u64);
+    }
+
+    let per_second = unit
+        .strip_suffix("ps")
+        .or_else(|| unit.strip_suffix("/s"))?;
+    // The case of the `b` is what separates bits from bytes.
+    let (prefix, bits_per_unit) = if let Some(prefix) = per_second.strip_suffix('b') {
+        (prefix, 1.0)
+    } else if let Some(prefix) = per_second.strip_suffix('B') {
+        (prefix, 8.0)
+    } else {
+        return None;
+    };
+    let multiplier = match prefix.to_ascii_lowercase().as_str() {
+        "" => 1.0,
+        "k" => 1e3,
+        "m" => 1e6,
+        "g" => 1e9,
+        "t" => 1e12,
+        _ => return None,
+    };
+    let bits = amount * multiplier * bits_per_unit;
+    (bits.is_finite() && bits <= u64::MAX as f64).then(|| bits.round() as u64)
+}
+
+impl RainmeterContext {
+    /// Read a data rate option and return it in **bits** per second, e.g.
+    /// `Limit=10Mbps` gives `10_000_000`.
+    ///
+    /// The unit's `b` decides the meaning: a lowercase `b` is bits and an
+    /// uppercase `B` is bytes (8 bits), so `1MB/s` (or `1MBps`) is
+    /// `8_000_000` but `1Mb/s` (or `1Mbps`) is `1_000_000`. The prefix may be
+    /// `k`, `M`, `G` or `T` in either case, and is decimal (`1k` = 1000) as is
+    /// usual for network rates. Amounts may be fractional (`1.5Gbps`) and a
+    /// bare number is bits per second.
+    ///
+    /// Invalid values are logged and fall back to `default`.
+    pub fn read_bit_rate(&self, key: &str, default: u64) -> u64 {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return default;
+        }
+        parse_bit_rate(&value).unwrap_or_else(|| {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: invalid data rate '{}'", key, value),
+            );
+            default
+        })
+    }
+
+    /// Read a number with an optional SI suffix, e.g. `Threshold=1.5k` gives
+    /// `1500` and `2M` gives `2000000`. Suffixes are case-sensitive, since
+    /// `m` (milli) and `M` (mega) differ; `K` is accepted for kilo and `u`
+    /// for micro. Invalid values are logged and fall back to `default`.
+    pub fn read_si_number(&self, key: &str, default: f64) -> f64 {
+        let value = self.read_string(key, "");
+        if value.trim().is_empty() {
+            return default;
+        }
+        parse_si_number(&value).unwrap_or_else(|| {
+            self.log(
+                RmLogLevel::LogWarning,
+                &format!("{}: invalid number '{}'", key, value),
+            );
+            default
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn parses_bit_rates() {
+        for (input, bits) in [
+            ("800bps", 800),
+            ("10Kbps", 10_000),
+            ("10kbps", 10_000),
+            ("10Mbps", 10_000_000),
+            ("2Gbps", 2_000_000_000),
+            ("1.5Gb/s", 1_500_000_000),
+            ("20MB/s", 160_000_000),
+            ("20MBps", 160_000_000),
+            (" 1 kB/s ", 8_000),
+            ("1200", 1_200),
+        ] {
+            assert_eq!(parse_bit_rate(input), Some(bits), "{}", input);
+        }
+        for input in ["", "fast", "10Mb", "10 Mbit/s", "10Xbps", "-5Mbps"] {
+            assert_eq!(parse_bit_rate(input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn logs_invalid_bit_rates() {
+        let rm = MockContext::new();
+        assert_eq!(rm.read_bit_rate("Limit", 42), 42);
+        rm.set("Limit", "1Mbps");
+        assert_eq!(rm.read_bit_rate("Limit", 42), 1_000_000);
+        rm.set("Limit", "lots");
+        assert_eq!(rm.read_bit_rate("Limit", 42), 42);
+        assert_eq!(
+            rm.warnings(),
+            ["Limit: invalid data rate 'lots'".to_string()]
+        );
+    }
+
+    #[test]
+    fn parses_si_numbers() {
+        for (input, expected) in [
+            ("1.5k", 1_500.0),
+            ("1.5K", 1_500.0),
+            ("2M", 2_000_000.0),
+            ("2m", 0.002),
+            (" 3 G ", 3e9),
+            ("4u", 4e-6),
+            ("4\u{b5}", 4e-6),
+            ("-250", -250.0),
+            ("1e3k", 1e6),
+        ] {
+            let value = parse_si_number(input).unwrap();
+            assert!(
+                (value - expected).abs() <= expected.abs() * 1e-12,
+                "{}",
+                input
+            );
+        }
+        for input in ["", "k", "2 kilo", "2X", "1.5.5k"] {
+            assert_eq!(parse_si_number(input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn formatted_si_numbers_parse_back() {
+        for value in [0.25, 1.0, 1_500.0, 2_000_000.0, 3.5e9, 0.004, -75_000.0] {
+            let text = format_si(value, 3);
+            let parsed = parse_si_number(&text).unwrap();
+            assert!((parsed - value).abs() <= value.abs() * 1e-9, "{}", text);
+        }
+    }
+
+    #[test]
+    fn logs_invalid_si_numbers() {
+        let rm = MockContext::new();
+        rm.set("Threshold", "1.5k");
+        assert_eq!(rm.read_si_number("Threshold", 1.0), 1_500.0);
+        rm.set("Threshold", "many");
+        assert_eq!(rm.read_si_number("Threshold", 1.0), 1.0);
+        assert_eq!(
+            rm.warnings(),
+            ["Threshold: invalid number 'many'".to_string()]
+        );
+    }
+}
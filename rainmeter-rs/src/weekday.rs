@@ -0,0 +1,100 @@
+use crate::RainmeterContext;
+use crate::options::lookup_mapped;
+use std::collections::HashSet;
+use windows::Win32::System::SystemInformation::GetLocalTime;
+
+/// A day of the week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+const WEEKDAY_NAMES: &[(&str, Weekday)] = &[
+    ("Mon", Weekday::Monday),
+    ("Monday", Weekday::Monday),
+    ("Tue", Weekday::Tuesday),
+    ("Tuesday", Weekday::Tuesday),
+    ("Wed", Weekday::Wednesday),
+    ("Wednesday", Weekday::Wednesday),
+    ("Thu", Weekday::Thursday),
+    ("Thursday", Weekday::Thursday),
+    ("Fri", Weekday::Friday),
+    ("Friday", Weekday::Friday),
+    ("Sat", Weekday::Saturday),
+    ("Saturday", Weekday::Saturday),
+    ("Sun", Weekday::Sunday),
+    ("Sunday", Weekday::Sunday),
+];
+
+impl Weekday {
+    /// Parse a three-letter or full English day name, ignoring case.
+    pub fn parse(name: &str) -> Option<Self> {
+        lookup_mapped(name, WEEKDAY_NAMES)
+    }
+
+    /// Today's day of the week, in local time.
+    pub fn today() -> Self {
+        let now = unsafe { GetLocalTime() };
+        // `wDayOfWeek` counts from Sunday = 0.
+        match now.wDayOfWeek {
+            1 => Weekday::Monday,
+            2 => Weekday::Tuesday,
+            3 => Weekday::Wednesday,
+            4 => Weekday::Thursday,
+            5 => Weekday::Friday,
+            6 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+}
+
+impl RainmeterContext {
+    /// Read a comma-separated set of days, e.g. `Days=Mon, Wed, Friday`.
+    /// Unknown names are logged and skipped.
+    pub fn read_weekdays(&self, key: &str) -> HashSet<Weekday> {
+        self.read_list_schema(key, ',', Weekday::parse)
+            .into_iter()
+            .collect()
+    }
+
+    /// Whether today (local time) is in `days`.
+    pub fn is_today_in(&self, days: &HashSet<Weekday>) -> bool {
+        days.contains(&Weekday::today())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn parses_short_and_full_names() {
+        assert_eq!(Weekday::parse("Mon"), Some(Weekday::Monday));
+        assert_eq!(Weekday::parse(" wednesday "), Some(Weekday::Wednesday));
+        assert_eq!(Weekday::parse("SUN"), Some(Weekday::Sunday));
+        assert_eq!(Weekday::parse("Thurs"), None);
+        assert_eq!(Weekday::parse(""), None);
+    }
+
+    #[test]
+    fn reads_weekday_sets() {
+        let rm = MockContext::new();
+        rm.set("Days", "Mon, wed, Friday, Funday, monday");
+        let days = rm.read_weekdays("Days");
+        assert_eq!(
+            days,
+            HashSet::from([Weekday::Monday, Weekday::Wednesday, Weekday::Friday])
+        );
+        assert_eq!(
+            rm.warnings(),
+            ["Days: skipping invalid entry 'Funday'".to_string()]
+        );
+    }
+}
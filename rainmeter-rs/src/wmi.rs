@@ -0,0 +1,192 @@
+//! WMI queries, behind the `wmi` feature.
+//!
+//! Each query initializes COM on the calling thread if it isn't already
+//! (multithreaded apartment), and uninitializes it again afterwards. If the
+//! thread already has an apartment, such as Rainmeter's main thread with its
+//! STA, that one is used and left alone. No COM objects outlive the call, so
+//! the results can be sent anywhere.
+//!
+//! Queries block until WMI answers, which can take hundreds of milliseconds
+//! for some classes. Run them on a worker thread (see
+//! [`CommandQueue`](crate::CommandQueue)) rather than in `update`.
+
+use crate::{RainmeterContext, RmError};
+use std::collections::HashMap;
+use windows::Win32::Foundation::RPC_E_CHANGED_MODE;
+use windows::Win32::System::Com::{
+    CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED, CoCreateInstance, CoInitializeEx,
+    CoSetProxyBlanket, CoUninitialize, EOAC_NONE, RPC_C_AUTHN_LEVEL_CALL,
+    RPC_C_IMP_LEVEL_IMPERSONATE,
+};
+use windows::Win32::System::Variant::{
+    VARIANT, VARIANT_ALPHABOOL, VT_BSTR, VariantChangeType, VariantClear,
+};
+use windows::Win32::System::Wmi::{
+    IWbemClassObject, IWbemLocator, WBEM_FLAG_FORWARD_ONLY, WBEM_FLAG_NONSYSTEM_ONLY,
+    WBEM_FLAG_RETURN_IMMEDIATELY, WBEM_INFINITE, WbemLocator,
+};
+use windows::core::BSTR;
+
+/// `RPC_C_AUTHN_WINNT` and `RPC_C_AUTHZ_NONE`, defined here to avoid pulling
+/// in `Win32_System_Rpc` for two constants.
+const RPC_C_AUTHN_WINNT: u32 = 10;
+const RPC_C_AUTHZ_NONE: u32 = 0;
+
+/// Uninitializes COM when dropped, if `init` initialized it.
+struct ComGuard {
+    initialized: bool,
+}
+
+impl ComGuard {
+    fn init() -> Result<Self, RmError> {
+        let hr = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+        if hr == RPC_E_CHANGED_MODE {
+            // The thread is already in an STA; use it as-is.
+            return Ok(Self { initialized: false });
+        }
+        hr.ok()?;
+        Ok(Self { initialized: true })
+    }
+}
+
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        if self.initialized {
+            unsafe { CoUninitialize() };
+        }
+    }
+}
+
+/// Run a WQL query against `ROOT\CIMV2` and return each row as a map of
+/// property name to value. Values are converted to strings the way
+/// `VariantChangeType` does (booleans as `True`/`False`); `NULL`s and values
+/// that don't convert, such as arrays, become empty strings.
+///
+/// ```rust,ignore
+/// let rows = rainmeter::wmi_query("SELECT Caption FROM Win32_OperatingSystem")?;
+/// let caption = &rows[0]["Caption"];
+/// ```
+pub fn wmi_query(query: &str) -> Result<Vec<HashMap<String, String>>, RmError> {
+    let _com = ComGuard::init()?;
+    unsafe {
+        let locator: IWbemLocator = CoCreateInstance(&WbemLocator, None, CLSCTX_INPROC_SERVER)?;
+        let services = locator.ConnectServer(
+            &BSTR::from("ROOT\\CIMV2"),
+            &BSTR::new(),
+            &BSTR::new(),
+            &BSTR::new(),
+            0,
+            &BSTR::new(),
+            None,
+        )?;
+        CoSetProxyBlanket(
+            &services,
+            RPC_C_AUTHN_WINNT,
+            RPC_C_AUTHZ_NONE,
+            None,
+            RPC_C_AUTHN_LEVEL_CALL,
+            RPC_C_IMP_LEVEL_IMPERSONATE,
+            None,
+            EOAC_NONE,
+        )?;
+        let results = services.ExecQuery(
+            &BSTR::from("WQL"),
+            &BSTR::from(query),
+            WBEM_FLAG_FORWARD_ONLY | WBEM_FLAG_RETURN_IMMEDIATELY,
+            None,
+        )?;
+
+        let mut rows = Vec::new();
+        loop {
+            let mut objects = [None];
+            let mut returned = 0;
+            results
+                .Next(WBEM_INFINITE, &mut objects, &mut returned)
+                .ok()?;
+            match objects[0].take() {
+                Some(object) if returned > 0 => rows.push(read_properties(&object)?),
+                _ => break,
+            }
+        }
+        Ok(rows)
+    }
+}
+
+unsafe fn read_properties(object: &IWbemClassObject) -> Result<HashMap<String, String>, RmError> {
+    let mut row = HashMap::new();
+    unsafe {
+        object.BeginEnumeration(WBEM_FLAG_NONSYSTEM_ONLY.0)?;
+        loop {
+            let mut name = BSTR::new();
+            let mut value = VARIANT::default();
+            object.Next(
+                0,
+                &mut name,
+                &mut value,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )?;
+            // `WBEM_S_NO_MORE_DATA` is a success code, so the end of the
+            // enumeration shows up as an empty name rather than an error.
+            if name.is_empty() {
+                let _ = VariantClear(&mut value);
+                break;
+            }
+            row.insert(name.to_string(), variant_to_string(&value));
+            let _ = VariantClear(&mut value);
+        }
+        object.EndEnumeration()?;
+    }
+    Ok(row)
+}
+
+unsafe fn variant_to_string(value: &VARIANT) -> String {
+    unsafe {
+        let mut converted = VARIANT::default();
+        let text = if VariantChangeType(&mut converted, value, VARIANT_ALPHABOOL, VT_BSTR).is_ok() {
+            converted.Anonymous.Anonymous.Anonymous.bstrVal.to_string()
+        } else {
+            String::new()
+        };
+        let _ = VariantClear(&mut converted);
+        text
+    }
+}
+
+impl RainmeterContext {
+    /// Read a WQL query option (e.g. `Query=SELECT * FROM Win32_Battery`) and
+    /// run it with [`wmi_query`]. See the module docs for threading.
+    pub fn read_wmi_query(&self, key: &str) -> Result<Vec<HashMap<String, String>>, RmError> {
+        let query = self.read_string(key, "");
+        if query.trim().is_empty() {
+            return Err(RmError::ParseFailed {
+                key: key.to_string(),
+                message: "no WQL query given".to_string(),
+            });
+        }
+        wmi_query(query.trim())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockContext;
+
+    #[test]
+    fn queries_the_operating_system() {
+        let rows = wmi_query("SELECT Caption FROM Win32_OperatingSystem").unwrap();
+        assert_eq!(rows.len(), 1);
+        assert!(!rows[0]["Caption"].is_empty(), "{:?}", rows);
+    }
+
+    #[test]
+    fn empty_query_options_are_rejected() {
+        let rm = MockContext::new();
+        rm.set("Query", "   ");
+        assert!(matches!(
+            rm.read_wmi_query("Query"),
+            Err(RmError::ParseFailed { .. })
+        ));
+    }
+}